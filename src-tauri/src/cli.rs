@@ -0,0 +1,163 @@
+//! Command line interface of the modloader
+//!
+//! Allows mod authors to package and check their mods without opening the app, e.g. `aw-modloader pack <folder> --output <archive>`.
+//! Subcommands run before the app is built and the DB is opened, so they also work while the app is running.
+use tauri::api::cli::{self, Matches};
+use tauri::{Assets, Context};
+
+use crate::config::ConfigManager;
+use crate::modmanager::{Diagnostic, ExportFormat, ModManager, PackageMetadata};
+
+/// Runs the subcommand provided on the command line. Returns the exit code if a subcommand has been run, None if the app starts normally.
+pub fn run<A: Assets>(context: &Context<A>, config_manager: &ConfigManager) -> Option<i32> {
+    let matches =
+        match cli::get_matches(context.config().tauri.cli.as_ref()?, context.package_info()) {
+            Ok(matches) => matches,
+            Err(err) => {
+                eprintln!("Failed to parse the command line arguments: {}", err);
+                return None;
+            }
+        };
+
+    let subcommand = matches.subcommand?;
+
+    attach_console();
+
+    match subcommand.name.as_str() {
+        "pack" => Some(pack(config_manager, &subcommand.matches)),
+        "lint" => Some(lint(&subcommand.matches)),
+        _ => None,
+    }
+}
+
+/// Packages a mod folder into an archive, see [`ModManager::package_folder`]
+fn pack(config_manager: &ConfigManager, matches: &Matches) -> i32 {
+    let (folder, output) = match (arg(matches, "folder"), arg(matches, "output")) {
+        (Some(folder), Some(output)) => (folder, output),
+        _ => {
+            eprintln!("The mod folder and the output archive are required");
+            return 1;
+        }
+    };
+
+    let format = match arg(matches, "format").as_deref() {
+        Some("zip") | None => ExportFormat::Zip,
+        Some("7z") => ExportFormat::SevenZip,
+        Some(format) => {
+            eprintln!("Unsupported archive format '{}', use zip or 7z", format);
+            return 1;
+        }
+    };
+
+    let metadata = PackageMetadata {
+        id: arg(matches, "id"),
+        name: arg(matches, "name"),
+        author: arg(matches, "author"),
+        version: arg(matches, "mod-version"),
+        info: arg(matches, "info"),
+        languages: arg(matches, "languages").map(|languages| {
+            languages
+                .split(',')
+                .map(|language| language.trim().to_owned())
+                .collect()
+        }),
+    };
+
+    let result = tauri::async_runtime::block_on(async {
+        let localization_path = config_manager
+            .read()
+            .await
+            .get_selected_installation()
+            .map(|installation| installation.get_localization_path(installation.get_language()));
+
+        ModManager::package_folder(
+            &folder,
+            metadata,
+            format,
+            &output,
+            localization_path.as_deref(),
+        )
+        .await
+    });
+
+    match result {
+        Ok(report) => {
+            if let Some(path_prefix) = report.path_prefix {
+                eprintln!(
+                    "Warning: The layout of the folder was misplaced, '{}' has been stripped from all paths",
+                    path_prefix
+                );
+            }
+
+            for folder in report.unknown_folders {
+                eprintln!(
+                    "Warning: The folder '{}' does not exist in the game",
+                    folder
+                );
+            }
+
+            println!("Packaged mod into {}", output);
+
+            0
+        }
+        Err(err) => {
+            eprintln!("Failed to package the mod: {:?}", err);
+
+            1
+        }
+    }
+}
+
+/// Checks a modinfo.json file and prints all of its problems, see [`ModManager::lint_mod_info`]
+fn lint(matches: &Matches) -> i32 {
+    let path = match arg(matches, "path") {
+        Some(path) => path,
+        None => {
+            eprintln!("The path of the modinfo.json file or mod folder is required");
+            return 1;
+        }
+    };
+
+    let diagnostics = match tauri::async_runtime::block_on(ModManager::lint_mod_info(&path)) {
+        Ok(diagnostics) => diagnostics,
+        Err(err) => {
+            eprintln!("Failed to read the modinfo.json file: {:?}", err);
+            return 1;
+        }
+    };
+
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        return 1;
+    }
+
+    println!("The modinfo.json file is valid");
+
+    0
+}
+
+/// The value of the argument, None if it has not been provided
+fn arg(matches: &Matches, name: &str) -> Option<String> {
+    matches
+        .args
+        .get(name)
+        .and_then(|arg| arg.value.as_str())
+        .map(str::to_owned)
+}
+
+/// Release builds on Windows are no console application, so the output would not be shown in the terminal the command has been run from
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // Fails if the modloader has not been started from a terminal, in which case there is nowhere to print to
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
@@ -0,0 +1,195 @@
+//! Location of the app data (mod registry and database) and moving it to a new location
+//!
+//! Moving the data is done on startup before the database is opened. Each step of the move is recorded in the config, so an interrupted move is resumed on the next start.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{portable_executable_dir, ConfigError, ModloaderConfig, Result, APP_SAVE_PATH};
+
+/// A move of the app data which has not been completed yet
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DataMove {
+    from: String,
+    to: String,
+    stage: DataMoveStage,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+enum DataMoveStage {
+    /// The data is copied to the new location. The old location is still in use.
+    Copying,
+    /// The new location is in use and the data in the old location is removed.
+    Cleanup,
+}
+
+/// The various ways a provided data path can be invalid
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "invalidDataPath")]
+pub enum InvalidDataPath {
+    /// Provided path points to a file instead of a directory
+    NotADirectory,
+    /// The modloader is not allowed to write into the provided directory
+    NotWritable,
+    /// The provided directory is the current data directory or located inside of it
+    InsideCurrentDataPath,
+    /// The provided directory already contains modloader data
+    AlreadyContainsData,
+}
+
+impl ModloaderConfig {
+    /// The directory which contains the mod registry and the database
+    pub fn get_data_path(&self) -> PathBuf {
+        self.data_path
+            .as_deref()
+            .map(resolve_path)
+            .unwrap_or_else(|| APP_SAVE_PATH.clone())
+    }
+
+    /// Schedules moving the provided data entries into the new data directory. The move is executed on the next start of the app by [`Self::resume_data_move()`].
+    pub async fn move_data_path(&mut self, path: String, entries: &[&str]) -> Result<()> {
+        let current_path = self.get_data_path();
+        let entries: Vec<String> = entries.iter().map(|entry| entry.to_string()).collect();
+
+        let (current_path, new_path) = tauri::async_runtime::spawn_blocking(move || {
+            let current_path = dunce::canonicalize(current_path)?;
+
+            fs::create_dir_all(&path)?;
+            let new_path = dunce::canonicalize(&path)?;
+
+            if !new_path.is_dir() {
+                return Err(ConfigError::InvalidDataPath(InvalidDataPath::NotADirectory));
+            }
+
+            if new_path.starts_with(&current_path) {
+                return Err(ConfigError::InvalidDataPath(
+                    InvalidDataPath::InsideCurrentDataPath,
+                ));
+            }
+
+            if entries.iter().any(|entry| new_path.join(entry).exists()) {
+                return Err(ConfigError::InvalidDataPath(
+                    InvalidDataPath::AlreadyContainsData,
+                ));
+            }
+
+            // Make sure the directory can be written to before scheduling the move
+            let probe_path = new_path.join(".write-probe");
+            fs::write(&probe_path, b"")
+                .and_then(|_| fs::remove_file(&probe_path))
+                .map_err(|_| ConfigError::InvalidDataPath(InvalidDataPath::NotWritable))?;
+
+            Ok((current_path, new_path))
+        })
+        .await??;
+
+        self.save_change(|config| {
+            config.pending_data_move = Some(DataMove {
+                from: store_path(&current_path),
+                to: store_path(&new_path),
+                stage: DataMoveStage::Copying,
+            })
+        })
+        .await
+    }
+
+    /// Continues a scheduled or interrupted data move. Must be called before the database is opened.
+    pub fn resume_data_move(&mut self, entries: &[&str]) -> Result<()> {
+        let mut data_move = match self.pending_data_move.clone() {
+            Some(data_move) => data_move,
+            None => return Ok(()),
+        };
+
+        let from = resolve_path(&data_move.from);
+        let to = resolve_path(&data_move.to);
+
+        if data_move.stage == DataMoveStage::Copying {
+            log::info!("Copying app data from {:?} to {:?}", from, to);
+
+            for entry in entries {
+                if from.join(entry).exists() {
+                    copy_dir(&from.join(entry), &to.join(entry))?;
+                }
+            }
+
+            // Switching to the new location and advancing the stage is a single atomic config write
+            data_move.stage = DataMoveStage::Cleanup;
+            self.data_path = Some(data_move.to.clone());
+            self.pending_data_move = Some(data_move);
+            self.write(true)?;
+        }
+
+        log::info!("Removing old app data from {:?}", from);
+
+        for entry in entries {
+            match fs::remove_dir_all(from.join(entry)) {
+                Ok(_) => (),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        self.pending_data_move = None;
+        self.write(true)?;
+
+        Ok(())
+    }
+}
+
+/// Converts a data path into the form stored in the config. In portable mode paths inside the folder of the executable are stored relative to it, so the portable folder can be moved as a whole.
+fn store_path(path: &Path) -> String {
+    // The data paths are canonicalized, so the folder of the executable has to be as well
+    let path = portable_executable_dir()
+        .and_then(|executable_dir| dunce::canonicalize(executable_dir).ok())
+        .and_then(|executable_dir| path.strip_prefix(executable_dir).ok().map(Path::to_owned))
+        .unwrap_or_else(|| path.to_owned());
+
+    path.to_string_lossy().to_string()
+}
+
+/// Converts a data path stored in the config into an absolute path. Relative paths are relative to the folder of the executable in portable mode.
+fn resolve_path(path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+
+    if path.is_absolute() {
+        return path;
+    }
+
+    match portable_executable_dir() {
+        Some(executable_dir) => executable_dir.join(path),
+        None => path,
+    }
+}
+
+/// Recursively copies a directory. Files which have been fully copied by an earlier, interrupted copy are skipped.
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+            continue;
+        }
+
+        // Files are copied to a temporary file first, so an existing target file is always complete
+        if target.exists() {
+            continue;
+        }
+
+        let mut temp_name = entry.file_name();
+        temp_name.push(".part");
+        let temp_target = to.join(temp_name);
+
+        fs::copy(entry.path(), &temp_target)?;
+        fs::rename(&temp_target, &target)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+//! Shared in-memory state of the app configuration
+use std::ffi::OsStr;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::modmanager::Mod;
+
+use super::{ModloaderConfig, Result, APP_SAVE_PATH, CONFIG_FILE_NAME};
+
+/// Event which is sent to the frontend containing the new config whenever the config changes
+pub const CONFIG_CHANGED_EVENT: &str = "config-changed";
+
+/// Holds the config of the app which is shared between all commands and the mod manager
+///
+/// The config is only read from disk once on startup. Afterwards all reads are served from memory and the config file is watched to pick up external edits.
+#[derive(Clone)]
+pub struct ConfigManager {
+    config: Arc<RwLock<ModloaderConfig>>,
+    app_handle: Arc<Mutex<Option<AppHandle>>>,
+    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+}
+
+impl ConfigManager {
+    /// Loads the config from disk
+    pub fn new() -> Result<Self> {
+        let config = tauri::async_runtime::block_on(ModloaderConfig::load_config())?;
+
+        Ok(Self {
+            config: Arc::new(RwLock::new(config)),
+            app_handle: Arc::new(Mutex::new(None)),
+            watcher: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Get read access to the config
+    pub async fn read(&self) -> RwLockReadGuard<'_, ModloaderConfig> {
+        self.config.read().await
+    }
+
+    /// Get write access to the config. The frontend is notified about any changes once the returned guard is dropped.
+    pub async fn write(&self) -> ConfigWriteGuard<'_> {
+        let config = self.config.write().await;
+        let previous = config.clone();
+
+        ConfigWriteGuard {
+            config,
+            previous,
+            manager: self,
+        }
+    }
+
+    /// Starts watching the config file for external edits, which are then loaded and sent to the frontend
+    pub fn watch(&self, app_handle: AppHandle) -> Result<()> {
+        *self
+            .app_handle
+            .lock()
+            .expect("Config app handle mutex has been poisoned") = Some(app_handle);
+
+        let manager = self.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(err) => {
+                        log::error!("Failed to watch config file: {}", err);
+                        return;
+                    }
+                };
+
+                if matches!(event.kind, EventKind::Access(_))
+                    || !event
+                        .paths
+                        .iter()
+                        .any(|path| path.file_name() == Some(OsStr::new(CONFIG_FILE_NAME)))
+                {
+                    return;
+                }
+
+                let manager = manager.clone();
+                tauri::async_runtime::spawn(async move { manager.reload().await });
+            })?;
+
+        // The directory is watched as the config file itself is replaced on every save
+        watcher.watch(&APP_SAVE_PATH, RecursiveMode::NonRecursive)?;
+
+        *self
+            .watcher
+            .lock()
+            .expect("Config watcher mutex has been poisoned") = Some(watcher);
+
+        Ok(())
+    }
+
+    /// Reloads the config from disk and notifies the frontend if it differs from the config in memory
+    ///
+    /// An invalid config file is not replaced with a backup like on startup, as it might still be written or edited by the user. The config in memory is kept instead.
+    /// The same applies to edits which change the path or language of an installation or remove it while mods are active in it, as the injected mod files could not be removed anymore.
+    async fn reload(&self) {
+        let mut config = self.config.write().await;
+
+        let new_config = tauri::async_runtime::spawn_blocking(|| {
+            ModloaderConfig::read(&APP_SAVE_PATH.join(CONFIG_FILE_NAME))
+        })
+        .await
+        .map_err(Into::into)
+        .and_then(|new_config| new_config);
+
+        match new_config {
+            Ok((new_config, _)) => {
+                if *config != new_config {
+                    if let Some(installation) = moved_active_installation(&config, &new_config) {
+                        log::error!(
+                            "The config file moves game installation '{}' which has active mods, keeping the current config. Deactivate its mods before changing its path or language.",
+                            installation
+                        );
+                        return;
+                    }
+
+                    log::info!("Config file has been changed externally, reloading");
+
+                    *config = new_config;
+                    self.emit_changed(&config);
+                }
+            }
+            Err(err) => log::error!(
+                "Failed to reload the changed config file, keeping the current config: {:?}",
+                err
+            ),
+        }
+    }
+
+    fn emit_changed(&self, config: &ModloaderConfig) {
+        if let Some(app_handle) = self
+            .app_handle
+            .lock()
+            .expect("Config app handle mutex has been poisoned")
+            .as_ref()
+        {
+            if let Err(err) = app_handle.emit_all(CONFIG_CHANGED_EVENT, config) {
+                log::error!("Failed to send config changed event to frontend: {}", err);
+            }
+        }
+    }
+}
+
+/// The first game installation with active mods whose path or language is changed or which is removed by the new config
+fn moved_active_installation(
+    config: &ModloaderConfig,
+    new_config: &ModloaderConfig,
+) -> Option<String> {
+    let active_installations = match Mod::installations_with_active_mods() {
+        Ok(active_installations) => active_installations,
+        Err(err) => {
+            log::error!("Failed to read the active mods: {:?}", err);
+            // Without knowing the active mods every change of an installation has to be treated as unsafe
+            config
+                .get_installations()
+                .iter()
+                .map(|installation| installation.get_name().to_owned())
+                .collect()
+        }
+    };
+
+    config
+        .get_installations()
+        .iter()
+        .filter(|installation| active_installations.contains(installation.get_name()))
+        .find(|installation| {
+            new_config
+                .get_installation(installation.get_name())
+                .map_or(true, |new_installation| {
+                    new_installation.get_path() != installation.get_path()
+                        || new_installation.get_language() != installation.get_language()
+                })
+        })
+        .map(|installation| installation.get_name().to_owned())
+}
+
+/// Write access to the config. Notifies the frontend about changes once dropped.
+pub struct ConfigWriteGuard<'a> {
+    config: RwLockWriteGuard<'a, ModloaderConfig>,
+    previous: ModloaderConfig,
+    manager: &'a ConfigManager,
+}
+
+impl Deref for ConfigWriteGuard<'_> {
+    type Target = ModloaderConfig;
+
+    fn deref(&self) -> &Self::Target {
+        &self.config
+    }
+}
+
+impl DerefMut for ConfigWriteGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.config
+    }
+}
+
+impl Drop for ConfigWriteGuard<'_> {
+    fn drop(&mut self) {
+        if *self.config != self.previous {
+            self.manager.emit_changed(&self.config);
+        }
+    }
+}
@@ -0,0 +1,662 @@
+//! Configuration functionality
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use ts_rs::TS;
+
+mod data_path;
+mod manager;
+mod watch_folder;
+
+pub use data_path::InvalidDataPath;
+pub use manager::ConfigManager;
+pub use watch_folder::WatchFolder;
+
+lazy_static! {
+    /// Directory of the config and log files
+    ///
+    /// In portable mode this is a folder next to the executable, otherwise the config directory of the user.
+    pub static ref APP_SAVE_PATH: PathBuf = {
+        let save_path = match portable_save_path() {
+            Some(save_path) => save_path,
+            None => ProjectDirs::from("com", "TeyKey1", "AW Modloader")
+                .expect("Failed to determine a valid savepath for the application data")
+                .config_dir()
+                .to_owned(),
+        };
+
+        if !save_path.exists() {
+            log::info!("App save directory does not exist yet, creating...");
+
+            fs::create_dir_all(&save_path).expect(
+                "Failed to create the configuration and storage directory of the application",
+            );
+        }
+
+        save_path
+    };
+
+    /// Directory of the mod registry and the database, which can be moved by the user
+    ///
+    /// # Caution
+    /// This is only evaluated once. Any pending data move has to be finished with [`ModloaderConfig::resume_data_move()`] before it is accessed.
+    pub static ref DATA_PATH: PathBuf = {
+        // Falling back to the default data path would open an empty DB and registry in the wrong place
+        let data_path = ModloaderConfig::load()
+            .expect("Failed to read the data path from the config file")
+            .get_data_path();
+
+        fs::create_dir_all(&data_path).expect("Failed to create the data directory of the application");
+
+        data_path
+    };
+}
+
+/// If this file exists next to the executable the app runs in portable mode
+const PORTABLE_MARKER_FILE_NAME: &str = "portable.txt";
+/// Folder next to the executable which contains all app data in portable mode
+const PORTABLE_SAVE_FOLDER_NAME: &str = "data";
+
+/// Get the save path of the portable mode if the portable marker file exists next to the executable
+fn portable_save_path() -> Option<PathBuf> {
+    portable_executable_dir().map(|executable_dir| executable_dir.join(PORTABLE_SAVE_FOLDER_NAME))
+}
+
+/// Get the folder of the executable if the app runs in portable mode
+fn portable_executable_dir() -> Option<PathBuf> {
+    let executable_path = std::env::current_exe().ok()?;
+    let executable_dir = executable_path.parent()?;
+
+    if executable_dir.join(PORTABLE_MARKER_FILE_NAME).is_file() {
+        Some(executable_dir.to_owned())
+    } else {
+        None
+    }
+}
+
+const CONFIG_FILE_NAME: &str = "config.json";
+/// Amount of rolling backups of the config file that are kept
+const CONFIG_BACKUP_COUNT: u32 = 3;
+/// The current version of the config file layout. Older versions are migrated on load by the [`CONFIG_MIGRATIONS`]
+const CONFIG_VERSION: u32 = 1;
+const AW_GAME_BASE_FOLDER_NAME: &str = "Armored Warfare MyCom";
+/// Name of the installation that is created out of the single game path and language of configs written by modloader v0.1.0
+pub const LEGACY_INSTALLATION_NAME: &str = "Default";
+
+/// Errors that can happen when working with the app configuration
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum ConfigError {
+    DeSerialization {
+        msg: String,
+    },
+    Io {
+        msg: String,
+    },
+    GameLanguageNotSupported,
+    InvalidGamePath(InvalidGamePath),
+    InvalidDataPath(InvalidDataPath),
+    /// No game installation with the provided name exists
+    InstallationNotExisting,
+    /// A game installation with the provided name already exists
+    InstallationAlreadyExisting,
+    /// The provided installation name is empty or contains path separators
+    InvalidInstallationName,
+    /// The provided watch folder does not exist or is not a directory
+    InvalidWatchFolder,
+    /// The config file was written by a newer modloader version which uses an unknown config layout
+    UnsupportedVersion {
+        version: u32,
+    },
+    TauriError {
+        msg: String,
+    },
+}
+
+/// The various ways a provided AW game path can be invalid
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "invalidGamePath")]
+pub enum InvalidGamePath {
+    NotExisting,
+    /// Provided path points to a file instead of a directory
+    NotADirectory,
+    InvalidPath,
+    /// If the folder name does not match with [`AW_GAME_BASE_FOLDER_NAME`]
+    InvalidFolderName,
+    /// If the localization folder cannot be found inside the provided game folder
+    LocalizationNotFound,
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(error: serde_json::Error) -> Self {
+        match error.classify() {
+            serde_json::error::Category::Io => Self::Io {
+                msg: error.to_string(),
+            },
+            _ => Self::DeSerialization {
+                msg: error.to_string(),
+            },
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io {
+            msg: format!("Failed to interact with app configuration IO: {}", error),
+        }
+    }
+}
+
+impl From<notify::Error> for ConfigError {
+    fn from(error: notify::Error) -> Self {
+        Self::Io {
+            msg: format!("Failed to watch the app configuration: {}", error),
+        }
+    }
+}
+
+impl From<tauri::Error> for ConfigError {
+    fn from(error: tauri::Error) -> Self {
+        Self::TauriError {
+            msg: error.to_string(),
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, ConfigError>;
+
+/// A single AW game installation with its own game path and language
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct GameInstallation {
+    /// Unique name of the installation which is used to identify it
+    name: String,
+    path: String,
+    language: String,
+}
+
+impl GameInstallation {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_path(&self) -> PathBuf {
+        PathBuf::from(&self.path)
+    }
+
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
+
+    /// The localization folder of the provided game language into which localization mods are injected
+    pub fn get_localization_path(&self, language: &str) -> PathBuf {
+        self.get_path().join("localization").join(language)
+    }
+}
+
+/// The app configuration
+///
+/// Access it through the [`ConfigManager`], which keeps the config in memory and in sync with the config file.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ModloaderConfig {
+    /// Layout version of the config file, see [`CONFIG_VERSION`]
+    version: u32,
+    app_language: Option<String>,
+    #[serde(default)]
+    installations: Vec<GameInstallation>,
+    /// Name of the installation all mod operations are currently applied to
+    selected_installation: Option<String>,
+    dark_theme: bool,
+    /// Directory of the mod registry and the database. If None the [`APP_SAVE_PATH`] is used
+    data_path: Option<String>,
+    /// A move of the data directory which has not been completed yet
+    pending_data_move: Option<data_path::DataMove>,
+    /// Folder which is watched for new mod archives
+    watch_folder: Option<WatchFolder>,
+}
+
+impl Default for ModloaderConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            app_language: None,
+            installations: vec![],
+            selected_installation: None,
+            dark_theme: false,
+            data_path: None,
+            pending_data_move: None,
+            watch_folder: None,
+        }
+    }
+}
+
+impl ModloaderConfig {
+    /// Loads an existing configuration file or creates a new default one, if not existing
+    ///
+    /// If the config file is corrupted the last good backup is restored.
+    async fn load_config() -> Result<Self> {
+        tauri::async_runtime::spawn_blocking(Self::load).await?
+    }
+
+    /// Blocking version of [`ModloaderConfig::load_config()`]
+    fn load() -> Result<Self> {
+        let config_path = APP_SAVE_PATH.join(CONFIG_FILE_NAME);
+
+        if !config_path.exists() {
+            log::info!("Could not find existing config.json file, creating a new one.");
+            let new_config = Self::default();
+
+            new_config.write(false)?;
+
+            return Ok(new_config);
+        }
+
+        match Self::read(&config_path) {
+            Ok((config, migrated)) => {
+                if migrated {
+                    config.write(true)?;
+                }
+
+                Ok(config)
+            }
+            Err(ConfigError::DeSerialization { msg }) => {
+                log::error!("Failed to parse config.json: {}", msg);
+
+                Self::restore_backup().ok_or(ConfigError::DeSerialization { msg })?
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Reads, migrates and validates the config file at the provided path. Returns the config and whether it has been migrated.
+    fn read(path: &Path) -> Result<(Self, bool)> {
+        let mut value: Value = serde_json::from_slice(&fs::read(path)?)?;
+
+        // Configs written by modloader v0.1.0 have no version
+        let version = match value.get("version") {
+            None => 0,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or_else(|| ConfigError::DeSerialization {
+                    msg: format!("Invalid config version {}", version),
+                })?,
+        };
+
+        if version > CONFIG_VERSION {
+            return Err(ConfigError::UnsupportedVersion { version });
+        }
+
+        for migration in CONFIG_MIGRATIONS[version as usize..].iter() {
+            migration(&mut value);
+        }
+
+        if let Some(object) = value.as_object_mut() {
+            object.insert("version".to_owned(), json!(CONFIG_VERSION));
+        }
+
+        let config: Self = serde_json::from_value(value)?;
+        config.validate()?;
+
+        Ok((config, version != CONFIG_VERSION))
+    }
+
+    /// Restores the newest backup which can be read successfully. Returns None if no usable backup exists.
+    fn restore_backup() -> Option<Result<Self>> {
+        for idx in 1..=CONFIG_BACKUP_COUNT {
+            let backup_path = backup_path(idx);
+
+            if !backup_path.exists() {
+                continue;
+            }
+
+            match Self::read(&backup_path) {
+                Ok((config, _)) => {
+                    log::warn!(
+                        "Restoring config from backup {}",
+                        backup_path.to_string_lossy()
+                    );
+
+                    // Do not rotate the backups, as this would replace a good backup with the corrupted config
+                    return Some(config.write(false).map(|_| config));
+                }
+                Err(err) => log::error!(
+                    "Failed to read config backup {}: {:?}",
+                    backup_path.to_string_lossy(),
+                    err
+                ),
+            }
+        }
+
+        None
+    }
+
+    /// Checks the config for semantic errors which cannot be detected by deserialization
+    fn validate(&self) -> Result<()> {
+        for (idx, installation) in self.installations.iter().enumerate() {
+            // The name is part of the DB tree names of the installation, a slash would mix up the trees of different installations
+            if !is_valid_installation_name(&installation.name) {
+                return Err(ConfigError::DeSerialization {
+                    msg: format!("Invalid game installation name '{}'", installation.name),
+                });
+            }
+
+            if self.installations[..idx]
+                .iter()
+                .any(|other| other.name == installation.name)
+            {
+                return Err(ConfigError::DeSerialization {
+                    msg: format!("Duplicate game installation '{}'", installation.name),
+                });
+            }
+        }
+
+        if let Some(selected_installation) = &self.selected_installation {
+            if self.get_installation(selected_installation).is_none() {
+                return Err(ConfigError::DeSerialization {
+                    msg: format!(
+                        "Selected game installation '{}' does not exist",
+                        selected_installation
+                    ),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Atomically writes the config file by writing a temporary file which then replaces the config file.
+    ///
+    /// If `rotate_backups` is true the current config file is kept as the newest backup.
+    fn write(&self, rotate_backups: bool) -> Result<()> {
+        let config_path = APP_SAVE_PATH.join(CONFIG_FILE_NAME);
+        let temp_path = APP_SAVE_PATH.join(format!("{}.tmp", CONFIG_FILE_NAME));
+
+        let mut temp_file = File::create(&temp_path)?;
+        serde_json::to_writer_pretty(&mut temp_file, self)?;
+        temp_file.sync_all()?;
+        drop(temp_file);
+
+        if rotate_backups && config_path.exists() {
+            for idx in (1..CONFIG_BACKUP_COUNT).rev() {
+                if backup_path(idx).exists() {
+                    fs::rename(backup_path(idx), backup_path(idx + 1))?;
+                }
+            }
+
+            fs::copy(&config_path, backup_path(1))?;
+        }
+
+        fs::rename(&temp_path, &config_path)?;
+
+        Ok(())
+    }
+
+    /// Sets game path and language of the selected installation. Creates the [`LEGACY_INSTALLATION_NAME`] installation if none is selected yet.
+    pub async fn set_advanced_config(
+        &mut self,
+        game_lang: String,
+        game_path_string: String,
+    ) -> Result<()> {
+        validate_game_path(&game_path_string)?;
+        let game_language = game_language_from_code(&game_lang)?;
+
+        let selected_installation = self.selected_installation.clone();
+
+        if let Some(name) = &selected_installation {
+            if self.get_installation(name).is_none() {
+                return Err(ConfigError::InstallationNotExisting);
+            }
+        }
+
+        self.save_change(|config| match selected_installation {
+            Some(name) => {
+                if let Some(installation) = config
+                    .installations
+                    .iter_mut()
+                    .find(|installation| installation.name == name)
+                {
+                    installation.path = game_path_string;
+                    installation.language = game_language;
+                }
+            }
+            None => {
+                config.installations.push(GameInstallation {
+                    name: LEGACY_INSTALLATION_NAME.to_owned(),
+                    path: game_path_string,
+                    language: game_language,
+                });
+                config.selected_installation = Some(LEGACY_INSTALLATION_NAME.to_owned());
+            }
+        })
+        .await
+    }
+
+    pub fn get_app_language(&self) -> Option<String> {
+        self.app_language.clone()
+    }
+
+    pub async fn set_app_language(&mut self, lang: Option<String>) -> Result<()> {
+        self.save_change(|config| config.app_language = lang).await
+    }
+
+    /// Game path of the selected installation
+    pub fn get_game_path(&self) -> Option<PathBuf> {
+        self.get_selected_installation()
+            .map(|installation| installation.get_path())
+    }
+
+    /// Game language of the selected installation
+    pub fn get_game_language(&self) -> Option<String> {
+        self.get_selected_installation()
+            .map(|installation| installation.language.clone())
+    }
+
+    pub fn get_installations(&self) -> &Vec<GameInstallation> {
+        &self.installations
+    }
+
+    pub fn get_installation(&self, name: &str) -> Option<&GameInstallation> {
+        self.installations
+            .iter()
+            .find(|installation| installation.name == name)
+    }
+
+    /// The installation all mod operations are currently applied to
+    pub fn get_selected_installation(&self) -> Option<&GameInstallation> {
+        self.selected_installation
+            .as_ref()
+            .and_then(|name| self.get_installation(name))
+    }
+
+    /// Adds a new game installation. The installation gets selected if no other installation is selected yet.
+    pub async fn add_installation(
+        &mut self,
+        name: String,
+        game_lang: String,
+        game_path_string: String,
+    ) -> Result<()> {
+        let name = name.trim().to_owned();
+
+        if !is_valid_installation_name(&name) {
+            return Err(ConfigError::InvalidInstallationName);
+        }
+
+        if self.get_installation(&name).is_some() {
+            return Err(ConfigError::InstallationAlreadyExisting);
+        }
+
+        validate_game_path(&game_path_string)?;
+        let game_language = game_language_from_code(&game_lang)?;
+
+        self.save_change(|config| {
+            if config.get_selected_installation().is_none() {
+                config.selected_installation = Some(name.clone());
+            }
+
+            config.installations.push(GameInstallation {
+                name,
+                path: game_path_string,
+                language: game_language,
+            });
+        })
+        .await
+    }
+
+    /// Removes a game installation from the config
+    ///
+    /// # Caution
+    /// This does not check if mods are still active in the installation. Make sure to deactivate them prior to removal.
+    pub async fn remove_installation(&mut self, name: &str) -> Result<()> {
+        if self.get_installation(name).is_none() {
+            return Err(ConfigError::InstallationNotExisting);
+        }
+
+        self.save_change(|config| {
+            config
+                .installations
+                .retain(|installation| installation.name != name);
+
+            if config.selected_installation.as_deref() == Some(name) {
+                config.selected_installation = config
+                    .installations
+                    .first()
+                    .map(|installation| installation.name.clone());
+            }
+        })
+        .await
+    }
+
+    pub async fn select_installation(&mut self, name: &str) -> Result<()> {
+        if self.get_installation(name).is_none() {
+            return Err(ConfigError::InstallationNotExisting);
+        }
+
+        self.save_change(|config| config.selected_installation = Some(name.to_owned()))
+            .await
+    }
+
+    pub fn get_dark_theme(&self) -> bool {
+        self.dark_theme
+    }
+
+    pub async fn set_dark_theme(&mut self, dark: bool) -> Result<()> {
+        self.save_change(|config| config.dark_theme = dark).await
+    }
+
+    /// Saves the config with the provided change applied. The config in memory is only changed once the config file has been written, so both never differ.
+    async fn save_change(&mut self, change: impl FnOnce(&mut Self)) -> Result<()> {
+        let mut config = self.clone();
+        change(&mut config);
+
+        *self = tauri::async_runtime::spawn_blocking(move || config.write(true).map(|_| config))
+            .await??;
+
+        Ok(())
+    }
+}
+
+/// Path of the config backup with the provided index. Index 1 is the newest backup.
+fn backup_path(idx: u32) -> PathBuf {
+    APP_SAVE_PATH.join(format!("{}.bak.{}", CONFIG_FILE_NAME, idx))
+}
+
+/// Migrations of the raw config file data. The migration at index n migrates the config from version n to n + 1.
+const CONFIG_MIGRATIONS: [fn(&mut Value); CONFIG_VERSION as usize] = [migrate_config_v0];
+
+/// Moves the single game path and language of modloader v0.1.0 configs into the [`LEGACY_INSTALLATION_NAME`] installation
+fn migrate_config_v0(value: &mut Value) {
+    let object = match value.as_object_mut() {
+        Some(object) => object,
+        None => return,
+    };
+
+    let game_path = object.remove("game_path");
+    let game_language = object.remove("game_language");
+
+    if let (Some(Value::String(path)), Some(Value::String(language))) = (game_path, game_language) {
+        log::info!(
+            "Migrating legacy game configuration into installation '{}'",
+            LEGACY_INSTALLATION_NAME
+        );
+
+        object.insert(
+            "installations".to_owned(),
+            json!([{
+                "name": LEGACY_INSTALLATION_NAME,
+                "path": path,
+                "language": language,
+            }]),
+        );
+        object.insert(
+            "selected_installation".to_owned(),
+            json!(LEGACY_INSTALLATION_NAME),
+        );
+    }
+}
+
+/// Installation names must not be empty and must not contain path separators, as they are used in the names of the DB trees of the installation
+fn is_valid_installation_name(name: &str) -> bool {
+    !name.trim().is_empty() && !name.contains(['/', '\\'])
+}
+
+/// Checks if the provided game path points to a valid AW game folder
+pub fn validate_game_path(game_path_string: &str) -> Result<()> {
+    let game_path = dunce::canonicalize(game_path_string)?;
+
+    if !game_path.is_dir() {
+        return Err(ConfigError::InvalidGamePath(InvalidGamePath::NotADirectory));
+    }
+
+    if !game_path
+        .file_name()
+        .ok_or(ConfigError::InvalidGamePath(InvalidGamePath::InvalidPath))?
+        .eq_ignore_ascii_case(AW_GAME_BASE_FOLDER_NAME)
+    {
+        return Err(ConfigError::InvalidGamePath(
+            InvalidGamePath::InvalidFolderName,
+        ));
+    }
+
+    if !game_path.read_dir()?.any(|entry| match entry {
+        Ok(entry) => entry.file_name().eq_ignore_ascii_case("localization"),
+        Err(_) => false,
+    }) {
+        return Err(ConfigError::InvalidGamePath(
+            InvalidGamePath::LocalizationNotFound,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Converts the language code used by the frontend into the name of the AW localization folder
+pub fn game_language_from_code(game_lang: &str) -> Result<String> {
+    match game_lang {
+        "en" => Ok("English".to_owned()),
+        "de" => Ok("German".to_owned()),
+        "fr" => Ok("French".to_owned()),
+        "pl" => Ok("Polish".to_owned()),
+        "ru" => Ok("Russian".to_owned()),
+        _ => Err(ConfigError::GameLanguageNotSupported),
+    }
+}
+
+/// Converts the name of an AW localization folder into the language code used by the frontend
+pub fn game_language_to_code(game_lang: &str) -> Result<String> {
+    match game_lang {
+        "English" => Ok("en".to_owned()),
+        "German" => Ok("de".to_owned()),
+        "French" => Ok("fr".to_owned()),
+        "Polish" => Ok("pl".to_owned()),
+        "Russian" => Ok("ru".to_owned()),
+        _ => Err(ConfigError::GameLanguageNotSupported),
+    }
+}
@@ -0,0 +1,33 @@
+//! Folder which is watched for new mod archives that are imported automatically
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::modmanager::OverwritePolicy;
+
+use super::{ConfigError, ModloaderConfig, Result};
+
+/// The watched folder and how archives of already existing mods are handled
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WatchFolder {
+    pub path: String,
+    pub overwrite_policy: OverwritePolicy,
+}
+
+impl ModloaderConfig {
+    pub fn get_watch_folder(&self) -> Option<&WatchFolder> {
+        self.watch_folder.as_ref()
+    }
+
+    /// Set the folder which is watched for new mod archives. None disables the watch folder.
+    pub async fn set_watch_folder(&mut self, watch_folder: Option<WatchFolder>) -> Result<()> {
+        if let Some(watch_folder) = &watch_folder {
+            if !Path::new(&watch_folder.path).is_dir() {
+                return Err(ConfigError::InvalidWatchFolder);
+            }
+        }
+
+        self.save_change(|config| config.watch_folder = watch_folder)
+            .await
+    }
+}
@@ -38,6 +38,14 @@ impl From<ConfigError> for AppError {
             ConfigError::Io { msg } => Self::Unrecoverable { msg },
             ConfigError::GameLanguageNotSupported => Self::Recoverable(error.into()),
             ConfigError::InvalidGamePath(_) => Self::Recoverable(error.into()),
+            ConfigError::InvalidDataPath(_) => Self::Recoverable(error.into()),
+            ConfigError::InstallationNotExisting => Self::Recoverable(error.into()),
+            ConfigError::InstallationAlreadyExisting => Self::Recoverable(error.into()),
+            ConfigError::InvalidInstallationName => Self::Recoverable(error.into()),
+            ConfigError::InvalidWatchFolder => Self::Recoverable(error.into()),
+            ConfigError::UnsupportedVersion { version } => Self::Unrecoverable {
+                msg: format!("The config file has been written by a newer modloader version (config version {}). Please update the modloader.", version),
+            },
             ConfigError::TauriError { msg } => Self::Unrecoverable { msg },
         }
     }
@@ -51,10 +59,21 @@ impl From<ModManagerError> for AppError {
             ModManagerError::DeSerialization { msg } => Self::Unrecoverable { msg },
             ModManagerError::InvalidArchive(_) => Self::Recoverable(error.into()),
             ModManagerError::ArchiveHandling { .. } => Self::Recoverable(error.into()),
+            ModManagerError::ArchiveCorrupted { .. } => Self::Recoverable(error.into()),
             ModManagerError::InvalidModInfo { .. } => Self::Recoverable(error.into()),
+            ModManagerError::InvalidPackManifest { .. } => Self::Recoverable(error.into()),
+            ModManagerError::ModloaderOutdated { .. } => Self::Recoverable(error.into()),
             ModManagerError::ModNotExisting => Self::Unrecoverable {
                 msg: String::from("Mod not existing, this is likely a bug."),
             },
+            ModManagerError::ModVersionNotExisting => Self::Recoverable(error.into()),
+            ModManagerError::ModOptionNotExisting { group, choice } => Self::Unrecoverable {
+                msg: format!(
+                    "Choice '{}' of mod option '{}' not existing, this is likely a bug.",
+                    choice, group
+                ),
+            },
+            ModManagerError::ModOptionsInUse { .. } => Self::Recoverable(error.into()),
             ModManagerError::ModAlreadyActive => Self::Unrecoverable {
                 msg: String::from("Mod already active, this is likely a bug."),
             },
@@ -63,10 +82,13 @@ impl From<ModManagerError> for AppError {
             ModManagerError::ModAlreadyDeactivated => Self::Unrecoverable {
                 msg: String::from("Mod already deactivated, this is likely a bug."),
             },
+            ModManagerError::NotADevelopmentMod => Self::Unrecoverable {
+                msg: String::from("Mod is not a development mod, this is likely a bug."),
+            },
             ModManagerError::AppNotInitialized => Self::Unrecoverable {
                 msg: String::from("Tried to perform an action which requires the modloader config to be initilized when it was not initialized yet, this is likely a bug."),
             },
-            ModManagerError::ModVersionMismatch { .. } => Self::Recoverable(error.into()),
+            ModManagerError::LanguageNotInstalled { .. } => Self::Recoverable(error.into()),
             ModManagerError::TauriError { msg } => Self::Unrecoverable { msg },
         }
     }
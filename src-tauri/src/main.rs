@@ -2,35 +2,41 @@
     all(not(debug_assertions), target_os = "windows"),
     windows_subsystem = "windows"
 )]
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 
 use lazy_static::lazy_static;
-use modmanager::{ModChangedEvent, ModManager};
+use modmanager::{ModChangedEvent, ModManager, MOD_REGISTRY_PATH};
 use simplelog::{
     ColorChoice, CombinedLogger, Config, LevelFilter, TermLogger, TerminalMode, WriteLogger,
 };
-use tauri::{Manager, RunEvent, State, Window};
+use tauri::{AppHandle, Manager, RunEvent, State, Window};
 
+mod cli;
 mod config;
 mod db;
 mod error;
 mod modmanager;
 
-use config::{ConfigError, ModloaderConfig};
+use config::{ConfigManager, GameInstallation, WatchFolder};
 use db::AppDb;
 use error::Result;
-use modmanager::Mod;
+use modmanager::{
+    AddModResult, Diagnostic, ExportFormat, ImportReport, Mod, ModInfo, ModVersion,
+    OverwritePolicy, PackageMetadata, PackageReport,
+};
 
 const DB_PATH: &str = "db";
 const DB_FLUSH_INTERVAL: u64 = 500;
 const DB_CACHE_CAPACITY: u64 = 10_485_760;
+/// All entries inside the data directory which are moved if the user changes the data directory
+const DATA_PATH_ENTRIES: [&str; 2] = [DB_PATH, MOD_REGISTRY_PATH];
 
 lazy_static! {
     /// The database of the application
     static ref DB: AppDb = {
         AppDb::open(
-            &config::APP_SAVE_PATH.join(DB_PATH),
+            &config::DATA_PATH.join(DB_PATH),
             DB_FLUSH_INTERVAL,
             DB_CACHE_CAPACITY,
         )
@@ -38,6 +44,27 @@ lazy_static! {
 }
 
 fn main() {
+    let context = tauri::generate_context!();
+
+    let config_manager = ConfigManager::new()
+        .map_err(|e| log::error!("Failed to load the config: {:?}", e))
+        .unwrap();
+
+    // Subcommands like packaging a mod run without starting the app or opening the DB
+    if let Some(exit_code) = cli::run(&context, &config_manager) {
+        std::process::exit(exit_code);
+    }
+
+    // Finish moving the data directory before the DB is opened
+    tauri::async_runtime::block_on(async {
+        config_manager
+            .write()
+            .await
+            .resume_data_move(&DATA_PATH_ENTRIES)
+    })
+    .map_err(|e| log::error!("Failed to move the data directory: {:?}", e))
+    .unwrap();
+
     let app = tauri::Builder::default()
         .invoke_handler(tauri::generate_handler![
             set_advanced_config,
@@ -47,17 +74,39 @@ fn main() {
             get_app_language,
             set_app_language,
             get_advanced_config,
+            get_installations,
+            add_installation,
+            remove_installation,
+            select_installation,
+            get_data_path,
+            set_data_path,
             add_new_mod,
+            add_mod_directory,
+            import_mods,
+            get_watch_folder,
+            set_watch_folder,
+            sync_mod,
+            export_mods,
+            get_folder_mod_info,
+            package_mod,
+            lint_mod_info,
             get_initial_mod_data,
             delete_mod,
             activate_mod,
-            deactivate_mod
+            deactivate_mod,
+            set_mod_languages,
+            merge_mods,
+            get_mod_versions,
+            get_mod_thumbnail,
+            switch_mod_version,
+            rollback_mod
         ])
         .manage(
-            ModManager::new()
+            ModManager::new(config_manager.clone())
                 .map_err(|e| log::error!("Failed to initialize ModManager: {:?}", e))
                 .unwrap(),
         )
+        .manage(config_manager)
         .setup(|app| {
             CombinedLogger::init(vec![
                 TermLogger::new(
@@ -74,7 +123,20 @@ fn main() {
             ])
             .expect("Failed to create logger");
 
+            app.state::<ConfigManager>()
+                .watch(app.handle())
+                .map_err(|e| log::error!("Failed to watch the config file: {:?}", e))
+                .unwrap();
+
+            // A missing watch folder must not prevent the app from starting
+            if let Err(err) =
+                tauri::async_runtime::block_on(app.state::<ModManager>().watch_folder(app.handle()))
+            {
+                log::error!("Failed to watch the watch folder: {:?}", err);
+            }
+
             let main_window = app.get_window("main").unwrap();
+            let app_handle = app.handle();
 
             tauri::async_runtime::spawn(async move {
                 let mod_tree = DB.open_tree(modmanager::DB_MOD_TREE_NAME);
@@ -88,9 +150,14 @@ fn main() {
                                 .unwrap()
                                 .parse()
                                 .expect("Failed to parse database key to u64");
-                            let modification: Mod = bincode::deserialize(&value)
+                            let mut modification: Mod = bincode::deserialize(&value)
                                 .expect("Failed to deserialize Mod struct from db data");
 
+                            app_handle
+                                .state::<ModManager>()
+                                .localize(&mut modification)
+                                .await;
+
                             ModChangedEvent::InsertUpdate(key, modification)
                         }
                         sled::Event::Remove { key } => {
@@ -111,7 +178,7 @@ fn main() {
 
             Ok(())
         })
-        .build(tauri::generate_context!())
+        .build(context)
         .expect("error while building tauri application");
 
     app.run(|_, event| {
@@ -124,8 +191,8 @@ fn main() {
 // Configuration related commands
 /// Check if the config contains all required configuration parameters and is initialized
 #[tauri::command]
-async fn config_is_initialized() -> Result<bool> {
-    let config = ModloaderConfig::load_config().await?;
+async fn config_is_initialized(config_manager: State<'_, ConfigManager>) -> Result<bool> {
+    let config = config_manager.read().await;
 
     Ok(config.get_game_path().is_some() && config.get_game_language().is_some())
 }
@@ -133,44 +200,42 @@ async fn config_is_initialized() -> Result<bool> {
 /// Set the advanced configuration of the app (game language and game path)
 #[tauri::command]
 async fn set_advanced_config(
+    config_manager: State<'_, ConfigManager>,
     game_lang: String,
     game_path: String,
     mod_manager: State<'_, ModManager>,
 ) -> Result<()> {
-    let mut config = ModloaderConfig::load_config().await?;
-
-    let current_game_lang = config.get_game_language();
-    let current_game_path = config.get_game_path();
-
-    let game_lang_cmp = match game_lang.as_str() {
-        "en" => Ok("English".to_owned()),
-        "de" => Ok("German".to_owned()),
-        "fr" => Ok("French".to_owned()),
-        "pl" => Ok("Polish".to_owned()),
-        "ru" => Ok("Russian".to_owned()),
-        _ => Err(ConfigError::GameLanguageNotSupported),
-    }?;
-
-    if current_game_lang.is_some()
-        && current_game_path.is_some()
-        && current_game_lang.unwrap() == game_lang_cmp
-        && current_game_path.unwrap().to_string_lossy() == game_path
-    {
-        // Nothing changed in the configuration
-        return Ok(());
-    }
+    let game_lang_cmp = config::game_language_from_code(&game_lang)?;
+    config::validate_game_path(&game_path)?;
+
+    let installation = config_manager
+        .read()
+        .await
+        .get_selected_installation()
+        .cloned();
+
+    if let Some(installation) = installation {
+        if installation.get_language() == game_lang_cmp
+            && installation.get_path().to_string_lossy() == game_path
+        {
+            // Nothing changed in the configuration
+            return Ok(());
+        }
 
-    // Deactivate all active mods as the config change requires a change of the mod installation folder inside the game installation
-    mod_manager.deactivate_all_mods().await?;
+        // Deactivate all active mods as the config change requires a change of the mod installation folder inside the game installation.
+        // The config is not locked meanwhile, as the mod manager reads it as well.
+        mod_manager.deactivate_all_mods(&installation).await?;
+    }
 
+    let mut config = config_manager.write().await;
     config.set_advanced_config(game_lang, game_path).await?;
 
     Ok(())
 }
 
 #[tauri::command]
-async fn configure_dark_mode(dark: bool) -> Result<bool> {
-    let mut config = ModloaderConfig::load_config().await?;
+async fn configure_dark_mode(config_manager: State<'_, ConfigManager>, dark: bool) -> Result<bool> {
+    let mut config = config_manager.write().await;
 
     config.set_dark_theme(dark).await?;
 
@@ -178,22 +243,22 @@ async fn configure_dark_mode(dark: bool) -> Result<bool> {
 }
 
 #[tauri::command]
-async fn get_dark_mode() -> Result<bool> {
-    let config = ModloaderConfig::load_config().await?;
+async fn get_dark_mode(config_manager: State<'_, ConfigManager>) -> Result<bool> {
+    let config = config_manager.read().await;
 
     Ok(config.get_dark_theme())
 }
 
 #[tauri::command]
-async fn get_app_language() -> Result<Option<String>> {
-    let config = ModloaderConfig::load_config().await?;
+async fn get_app_language(config_manager: State<'_, ConfigManager>) -> Result<Option<String>> {
+    let config = config_manager.read().await;
 
     Ok(config.get_app_language())
 }
 
 #[tauri::command]
-async fn set_app_language(lang: String) -> Result<()> {
-    let mut config = ModloaderConfig::load_config().await?;
+async fn set_app_language(config_manager: State<'_, ConfigManager>, lang: String) -> Result<()> {
+    let mut config = config_manager.write().await;
 
     config.set_app_language(Some(lang)).await?;
 
@@ -201,8 +266,10 @@ async fn set_app_language(lang: String) -> Result<()> {
 }
 
 #[tauri::command]
-async fn get_advanced_config() -> Result<(Option<String>, Option<String>)> {
-    let config = ModloaderConfig::load_config().await?;
+async fn get_advanced_config(
+    config_manager: State<'_, ConfigManager>,
+) -> Result<(Option<String>, Option<String>)> {
+    let config = config_manager.read().await;
 
     Ok((
         config.get_game_language(),
@@ -212,10 +279,84 @@ async fn get_advanced_config() -> Result<(Option<String>, Option<String>)> {
     ))
 }
 
+/// Get all configured game installations and the name of the selected one
+#[tauri::command]
+async fn get_installations(
+    config_manager: State<'_, ConfigManager>,
+) -> Result<(Vec<GameInstallation>, Option<String>)> {
+    let config = config_manager.read().await;
+
+    Ok((
+        config.get_installations().clone(),
+        config
+            .get_selected_installation()
+            .map(|installation| installation.get_name().to_owned()),
+    ))
+}
+
+#[tauri::command]
+async fn add_installation(
+    config_manager: State<'_, ConfigManager>,
+    name: String,
+    game_lang: String,
+    game_path: String,
+) -> Result<()> {
+    let mut config = config_manager.write().await;
+
+    config.add_installation(name, game_lang, game_path).await?;
+
+    Ok(())
+}
+
+/// Remove a game installation. All mods active in the installation are deactivated prior to removal
+#[tauri::command]
+async fn remove_installation(mod_manager: State<'_, ModManager>, name: String) -> Result<()> {
+    mod_manager.remove_installation(&name).await?;
+
+    Ok(())
+}
+
+/// Select the game installation all mod operations are applied to
+#[tauri::command]
+async fn select_installation(config_manager: State<'_, ConfigManager>, name: String) -> Result<()> {
+    let mut config = config_manager.write().await;
+
+    config.select_installation(&name).await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_data_path(config_manager: State<'_, ConfigManager>) -> Result<String> {
+    let config = config_manager.read().await;
+
+    Ok(config.get_data_path().to_string_lossy().to_string())
+}
+
+/// Move the mod registry and the database into a new directory. The app restarts to execute the move.
+#[tauri::command]
+async fn set_data_path(
+    config_manager: State<'_, ConfigManager>,
+    app_handle: AppHandle,
+    path: String,
+) -> Result<()> {
+    let mut config = config_manager.write().await;
+
+    config.move_data_path(path, &DATA_PATH_ENTRIES).await?;
+
+    // The app does not return from restarting, so the config is unlocked before
+    drop(config);
+
+    DB.flush();
+    app_handle.restart();
+
+    Ok(())
+}
+
 // Mod related commands
 #[tauri::command]
-fn get_initial_mod_data(mod_manager: State<'_, ModManager>) -> Result<HashMap<u64, Mod>> {
-    let data = mod_manager.get_initial_mod_data()?;
+async fn get_initial_mod_data(mod_manager: State<'_, ModManager>) -> Result<HashMap<u64, Mod>> {
+    let data = mod_manager.get_initial_mod_data().await?;
 
     Ok(data)
 }
@@ -225,12 +366,118 @@ async fn add_new_mod(
     mod_manager: State<'_, ModManager>,
     window: Window,
     archive_path: String,
+) -> Result<AddModResult> {
+    let result = mod_manager.add_mod(&archive_path, window).await?;
+
+    Ok(result)
+}
+
+/// Import multiple mod archives or folders containing mod archives at once
+#[tauri::command]
+async fn import_mods(
+    mod_manager: State<'_, ModManager>,
+    window: Window,
+    paths: Vec<String>,
+    policy: OverwritePolicy,
+) -> Result<ImportReport> {
+    let report = mod_manager.import_mods(paths, policy, window).await?;
+
+    Ok(report)
+}
+
+/// The folder which is watched for new mod archives, None if no watch folder is configured
+#[tauri::command]
+async fn get_watch_folder(config_manager: State<'_, ConfigManager>) -> Result<Option<WatchFolder>> {
+    let config = config_manager.read().await;
+
+    Ok(config.get_watch_folder().cloned())
+}
+
+/// Set the folder which is watched for new mod archives. None disables the watch folder.
+#[tauri::command]
+async fn set_watch_folder(
+    mod_manager: State<'_, ModManager>,
+    app_handle: AppHandle,
+    watch_folder: Option<WatchFolder>,
 ) -> Result<()> {
-    mod_manager.add_mod(&archive_path, window).await?;
+    mod_manager
+        .set_watch_folder(watch_folder, app_handle)
+        .await?;
 
     Ok(())
 }
 
+/// Add a mod from an unpacked folder, either packed into the registry or as development mod which is read from the folder
+#[tauri::command]
+async fn add_mod_directory(
+    mod_manager: State<'_, ModManager>,
+    window: Window,
+    directory_path: String,
+    development: bool,
+) -> Result<AddModResult> {
+    let result = mod_manager
+        .add_mod_directory(&directory_path, development, window)
+        .await?;
+
+    Ok(result)
+}
+
+/// Apply the changes of the folder of a development mod to the game
+#[tauri::command]
+async fn sync_mod(mod_manager: State<'_, ModManager>, uid: u64) -> Result<()> {
+    mod_manager.sync_mod(uid).await?;
+
+    Ok(())
+}
+
+/// Export the mods as archive, multiple mods are exported as mod pack
+#[tauri::command]
+async fn export_mods(
+    mod_manager: State<'_, ModManager>,
+    uids: Vec<u64>,
+    format: ExportFormat,
+    target_path: String,
+) -> Result<()> {
+    mod_manager.export_mods(uids, format, &target_path).await?;
+
+    Ok(())
+}
+
+/// Read the modinfo.json file of a mod folder
+#[tauri::command]
+async fn get_folder_mod_info(
+    mod_manager: State<'_, ModManager>,
+    folder_path: String,
+) -> Result<Option<ModInfo>> {
+    let mod_info = mod_manager.get_folder_mod_info(&folder_path).await?;
+
+    Ok(mod_info)
+}
+
+/// Check a modinfo.json file or the modinfo.json file of a mod folder and return all of its problems
+#[tauri::command]
+async fn lint_mod_info(path: String) -> Result<Vec<Diagnostic>> {
+    let diagnostics = ModManager::lint_mod_info(&path).await?;
+
+    Ok(diagnostics)
+}
+
+/// Package a mod folder into a ready to share mod archive
+#[tauri::command]
+async fn package_mod(
+    mod_manager: State<'_, ModManager>,
+    folder_path: String,
+    metadata: PackageMetadata,
+    format: ExportFormat,
+    target_path: String,
+) -> Result<PackageReport> {
+    let report = mod_manager
+        .package_mod(&folder_path, metadata, format, &target_path)
+        .await?;
+
+    Ok(report)
+}
+
 #[tauri::command]
 async fn delete_mod(mod_manager: State<'_, ModManager>, uid: u64) -> Result<()> {
     mod_manager.delete_mod(uid).await?;
@@ -239,8 +486,12 @@ async fn delete_mod(mod_manager: State<'_, ModManager>, uid: u64) -> Result<()>
 }
 
 #[tauri::command]
-async fn activate_mod(mod_manager: State<'_, ModManager>, uid: u64) -> Result<()> {
-    mod_manager.activate_mod(uid).await?;
+async fn activate_mod(
+    mod_manager: State<'_, ModManager>,
+    uid: u64,
+    options: Option<BTreeMap<String, String>>,
+) -> Result<()> {
+    mod_manager.activate_mod(uid, options).await?;
 
     Ok(())
 }
@@ -251,3 +502,66 @@ async fn deactivate_mod(mod_manager: State<'_, ModManager>, uid: u64) -> Result<
 
     Ok(())
 }
+
+/// Set the game languages a mod is injected into. An empty list or None resets to the game language of the installation.
+#[tauri::command]
+async fn set_mod_languages(
+    mod_manager: State<'_, ModManager>,
+    uid: u64,
+    languages: Option<Vec<String>>,
+) -> Result<()> {
+    mod_manager.set_mod_languages(uid, languages).await?;
+
+    Ok(())
+}
+
+/// Merge a duplicate mod into another mod. The duplicate is deleted and recognized as the other mod in the future.
+#[tauri::command]
+async fn merge_mods(
+    mod_manager: State<'_, ModManager>,
+    uid: u64,
+    duplicate_uid: u64,
+) -> Result<()> {
+    mod_manager.merge_mods(uid, duplicate_uid).await?;
+
+    Ok(())
+}
+
+/// Get the thumbnail of the preview image of a mod as PNG image. None if the mod has no preview image.
+#[tauri::command]
+async fn get_mod_thumbnail(
+    mod_manager: State<'_, ModManager>,
+    uid: u64,
+) -> Result<Option<Vec<u8>>> {
+    let thumbnail = mod_manager.get_mod_thumbnail(uid).await?;
+
+    Ok(thumbnail)
+}
+
+/// Get all versions of a mod kept in the registry, ordered from oldest to newest. The last entry is the current version.
+#[tauri::command]
+fn get_mod_versions(mod_manager: State<'_, ModManager>, uid: u64) -> Result<Vec<ModVersion>> {
+    let versions = mod_manager.get_mod_versions(uid)?;
+
+    Ok(versions)
+}
+
+/// Switch a mod to another version kept in the registry. Active mods are re-injected with the new version.
+#[tauri::command]
+async fn switch_mod_version(
+    mod_manager: State<'_, ModManager>,
+    uid: u64,
+    fingerprint: String,
+) -> Result<()> {
+    mod_manager.switch_mod_version(uid, &fingerprint).await?;
+
+    Ok(())
+}
+
+/// Switch a mod back to the version it had before the last update
+#[tauri::command]
+async fn rollback_mod(mod_manager: State<'_, ModManager>, uid: u64) -> Result<()> {
+    mod_manager.rollback_mod(uid).await?;
+
+    Ok(())
+}
@@ -1,13 +1,29 @@
 //! Functions to manage and interact with the mod archives
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fs::File;
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 
+use compress_tools::{ArchiveContents, ArchiveIterator, Ownership};
+use semver::Version;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use ts_rs::TS;
 
+use crate::config;
+
 use super::error::{ModManagerError, Result};
 use super::injection::InjectionType;
+use super::layout;
+use super::lint;
+use super::localized::LocalizedText;
+use super::mapping::FileRule;
+use super::options::OptionGroup;
+use super::pack::{self, PackManifest, PACK_MANIFEST_FILE};
+
+/// Name of the file containing the changelog of the mod
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
 
 /// Errors that can happen when handling the mod archives
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -18,16 +34,151 @@ pub enum InvalidArchive {
     PathNotExisting,
     /// Provided path to the mod archive is not a file
     PathNotFile,
-    /// Provided file has no extension
-    NoExtension,
-    /// Invalid file extension
-    InvalidExtension,
+    /// Provided path to the mod folder is not a directory
+    PathNotDirectory,
+    /// Provided file is empty
+    EmptyFile,
+    /// The content of the provided file is not an archive format supported by the modloader
+    UnsupportedFormat,
+    /// The archive contains the localization folders of several game languages, which can not be injected as one mod
+    MultipleLocalizations,
+}
+
+/// The archive formats supported by the modloader
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    SevenZip,
+    Rar,
+    Tar,
+    TarGz,
+    TarXz,
+}
+
+impl ArchiveFormat {
+    /// Number of bytes at the start of a file required to detect all formats. Tar files have their magic bytes at offset 257.
+    const MAGIC_BYTES_LENGTH: usize = 262;
+
+    /// Detects the archive format of the file by its magic bytes
+    ///
+    /// Compressed streams are only accepted if they contain a tar archive, as libarchive does not support other content inside of them.
+    pub fn detect<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut header = Vec::with_capacity(Self::MAGIC_BYTES_LENGTH);
+        File::open(path)?
+            .take(Self::MAGIC_BYTES_LENGTH as u64)
+            .read_to_end(&mut header)?;
+
+        if header.is_empty() {
+            return Err(ModManagerError::InvalidArchive(InvalidArchive::EmptyFile));
+        }
+
+        match Self::from_magic_bytes(&header) {
+            Some(Self::TarGz | Self::TarXz) if !Self::contains_tar(path)? => Err(
+                ModManagerError::InvalidArchive(InvalidArchive::UnsupportedFormat),
+            ),
+            Some(format) => Ok(format),
+            None => Err(ModManagerError::InvalidArchive(
+                InvalidArchive::UnsupportedFormat,
+            )),
+        }
+    }
+
+    /// Whether the decompressed content of the file starts with a tar header
+    fn contains_tar(path: &Path) -> Result<bool> {
+        let mut header = HeaderWriter::default();
+
+        // The writer aborts the decompression once the tar header has been read, so the error is expected
+        let _ = compress_tools::uncompress_data(File::open(path)?, &mut header);
+
+        Ok(Self::is_tar(&header.0))
+    }
+
+    fn is_tar(header: &[u8]) -> bool {
+        header.len() >= Self::MAGIC_BYTES_LENGTH && &header[257..262] == b"ustar"
+    }
+
+    fn from_magic_bytes(header: &[u8]) -> Option<Self> {
+        if header.starts_with(b"PK\x03\x04")
+            || header.starts_with(b"PK\x05\x06")
+            || header.starts_with(b"PK\x07\x08")
+        {
+            Some(Self::Zip)
+        } else if header.starts_with(&[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C]) {
+            Some(Self::SevenZip)
+        } else if header.starts_with(b"Rar!\x1A\x07") {
+            Some(Self::Rar)
+        } else if header.starts_with(&[0x1F, 0x8B]) {
+            Some(Self::TarGz)
+        } else if header.starts_with(&[0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00]) {
+            Some(Self::TarXz)
+        } else if Self::is_tar(header) {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    /// Normalized file extension of the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::SevenZip => "7z",
+            ArchiveFormat::Rar => "rar",
+            ArchiveFormat::Tar => "tar",
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::TarXz => "tar.xz",
+        }
+    }
+
+    /// Guesses the format from a file extension. Only used for files which can not be inspected.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "zip" => Some(Self::Zip),
+            "7z" => Some(Self::SevenZip),
+            "rar" => Some(Self::Rar),
+            "tar" => Some(Self::Tar),
+            "gz" | "tgz" | "tar.gz" => Some(Self::TarGz),
+            "xz" | "txz" | "tar.xz" => Some(Self::TarXz),
+            _ => None,
+        }
+    }
+}
+
+/// Collects the header of a tar archive from a decompressed stream and aborts the decompression afterwards
+#[derive(Default)]
+struct HeaderWriter(Vec<u8>);
+
+impl HeaderWriter {
+    /// Size of a tar header block
+    const LENGTH: usize = 512;
+}
+
+impl Write for HeaderWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.0.len() >= Self::LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "The tar header has been read",
+            ));
+        }
+
+        let length = buf.len().min(Self::LENGTH - self.0.len());
+        self.0.extend_from_slice(&buf[..length]);
+
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 /// Intermediate Struct used to open a mod archive and read its contents
+#[derive(Clone)]
 pub struct ModArchive {
     pub name: String,
-    pub extension: String,
+    pub format: ArchiveFormat,
     pub path: PathBuf,
 }
 
@@ -45,15 +196,12 @@ impl ModArchive {
             return Err(ModManagerError::InvalidArchive(InvalidArchive::PathNotFile));
         }
 
-        // Get archive name and extension
-        let archive_extension = path
-            .extension()
-            .ok_or(ModManagerError::InvalidArchive(InvalidArchive::NoExtension))?
-            .to_str()
-            .ok_or(ModManagerError::InvalidArchive(
-                InvalidArchive::InvalidExtension,
-            ))?
-            .to_owned();
+        let detect_path = path.clone();
+        let archive_format =
+            tauri::async_runtime::spawn_blocking(move || ArchiveFormat::detect(detect_path))
+                .await??;
+
+        // Get archive name
         let archive_name = path
             .file_name()
             .unwrap_or_else(|| OsStr::new("Unknown"))
@@ -66,79 +214,496 @@ impl ModArchive {
 
         Ok(Self {
             name: archive_name,
-            extension: archive_extension,
+            format: archive_format,
             path,
         })
     }
 
     pub async fn get_modinfo(&self) -> Result<Option<ModInfo>> {
+        let mod_info_file = match self.read_file("modinfo.json").await? {
+            Some(mod_info_file) => mod_info_file,
+            None => return Ok(None),
+        };
+
+        log::debug!("Found modinfo file, trying to deserialize...");
+
+        ModInfo::parse(&mod_info_file).map(Some)
+    }
+
+    /// Reads the CHANGELOG.md file of the archive if it exists
+    pub async fn get_changelog_file(&self) -> Result<Option<String>> {
+        Ok(self
+            .read_file(CHANGELOG_FILE)
+            .await?
+            .map(|changelog| String::from_utf8_lossy(&changelog).to_string()))
+    }
+
+    /// Reads the file with the provided name (case insensitive). If the archive contains multiple files with this name, the one closest to the base of the archive is read, so files inside wrapper folders are found as well.
+    async fn read_file(&self, file_name: &str) -> Result<Option<Vec<u8>>> {
         let path_clone = self.path.clone();
-        tauri::async_runtime::spawn_blocking::<_, Result<Option<ModInfo>>>(|| {
+        let file_name = file_name.to_owned();
+        tauri::async_runtime::spawn_blocking::<_, Result<Option<Vec<u8>>>>(move || {
             let archive_file = File::open(path_clone)?;
 
             let files = compress_tools::list_archive_files(&archive_file)?;
 
-            // Try to find modinfo.json file
-            for path in files.iter() {
-                if !path.eq_ignore_ascii_case("modinfo.json") {
-                    continue;
-                }
+            let path = match find_file(&files, &file_name) {
+                Some(path) => path,
+                None => return Ok(None),
+            };
 
-                log::debug!("Found modinfo file {}, trying to deserialize...", path);
+            let mut file = vec![];
+            compress_tools::uncompress_archive_file(&archive_file, &mut file, path)?;
+
+            Ok(Some(file))
+        })
+        .await?
+    }
 
-                let mut mod_info_file = vec![];
-                compress_tools::uncompress_archive_file(&archive_file, &mut mod_info_file, path)?;
+    /// Detects common mistakes in the folder layout of the archive. Returns the prefix which has to be stripped from the archive paths to get the paths inside the game.
+    ///
+    /// If the files of the mod are placed by file rules, the prefix is the folder of the modinfo.json instead.
+    pub async fn detect_layout_prefix(
+        &self,
+        has_file_rules: bool,
+        localization_path: Option<&Path>,
+    ) -> Result<Option<String>> {
+        let path = self.path.clone();
+        let localization_path = localization_path.map(Path::to_path_buf);
+        tauri::async_runtime::spawn_blocking(move || {
+            let archive = File::open(path)?;
+            let entries = compress_tools::list_archive_files(archive)?;
 
-                return Ok(Some(
-                    serde_json::from_slice::<ModInfo>(&mod_info_file)
-                        .map_err(|e| ModManagerError::InvalidModInfo { msg: e.to_string() })?,
-                ));
+            if has_file_rules {
+                return Ok(layout::modinfo_prefix(&entries));
             }
 
-            Ok(None)
+            layout::detect_prefix(&entries, localization_path.as_deref())
         })
         .await?
     }
 
-    /// Gets all paths of the files and dirs contained in an archive. Automatically removes modinfo.json path if it exists as it does not need to be injected into the game.
-    pub async fn get_archive_dirs_and_files(&self) -> Result<(Vec<String>, Vec<String>)> {
+    /// Detects if the archive is a mod pack and returns the folders of the contained mods, including the trailing slash. Returns None if the archive contains a single mod.
+    pub async fn detect_pack_folders(&self) -> Result<Option<Vec<String>>> {
         let path = self.path.clone();
         tauri::async_runtime::spawn_blocking(move || {
             let archive = File::open(path)?;
-            let mut files = compress_tools::list_archive_files(archive)?;
-            let mut dirs = vec![];
+            let entries = compress_tools::list_archive_files(&archive)?;
 
-            let mut idx = 0;
+            let manifest_path = match find_file(&entries, PACK_MANIFEST_FILE) {
+                Some(manifest_path) => manifest_path,
+                None => return Ok(pack::detect_mod_folders(&entries)),
+            };
 
-            while idx < files.len() {
-                let path = &files[idx];
+            log::debug!("Found mod pack manifest, trying to deserialize...");
 
-                if path.ends_with('/') {
-                    dirs.push(files.remove(idx));
-                    continue;
-                }
+            let mut manifest_file = vec![];
+            compress_tools::uncompress_archive_file(&archive, &mut manifest_file, manifest_path)?;
 
-                if path.contains("modinfo.json") {
-                    files.remove(idx);
-                    continue;
-                }
+            PackManifest::parse(&manifest_file)?
+                .mod_folders(&entries, manifest_path)
+                .map(Some)
+        })
+        .await?
+    }
 
-                idx += 1;
+    /// Extracts the whole archive into the provided folder
+    pub async fn extract(&self, target_path: &Path) -> Result<()> {
+        let path = self.path.clone();
+        let target_path = target_path.to_owned();
+        tauri::async_runtime::spawn_blocking(move || {
+            let archive = File::open(path)?;
+            compress_tools::uncompress_archive(archive, &target_path, Ownership::Ignore)?;
+
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Calculates the SHA-256 hash of the archive file as lowercase hex string
+    pub async fn get_fingerprint(&self) -> Result<String> {
+        let path = self.path.clone();
+        tauri::async_runtime::spawn_blocking(move || hash_file(path)).await?
+    }
+
+    /// Calculates the SHA-256 hash of every mod file contained in the archive, keyed by the path inside the game. The modinfo.json and CHANGELOG.md files and files outside of the path prefix are skipped.
+    pub async fn get_file_hashes(&self, path_prefix: &str) -> Result<HashMap<String, String>> {
+        let path = self.path.clone();
+        let path_prefix = path_prefix.to_owned();
+        tauri::async_runtime::spawn_blocking::<_, Result<HashMap<String, String>>>(move || {
+            let entries = compress_tools::list_archive_files(File::open(&path)?)?;
+            let changelog = find_file(&entries, CHANGELOG_FILE).cloned();
+            let archive = File::open(path)?;
+            let mut hashes = HashMap::new();
+            let mut current_entry: Option<(String, Sha256)> = None;
+
+            for content in ArchiveIterator::from_read(archive)? {
+                match content {
+                    ArchiveContents::StartOfEntry(path, _) => {
+                        current_entry = Some((path, Sha256::new()));
+                    }
+                    ArchiveContents::DataChunk(data) => {
+                        if let Some((_, hasher)) = current_entry.as_mut() {
+                            hasher.update(&data);
+                        }
+                    }
+                    ArchiveContents::EndOfEntry => {
+                        if let Some((path, hasher)) = current_entry.take() {
+                            if Some(&path) == changelog.as_ref() {
+                                continue;
+                            }
+
+                            let path = match path.strip_prefix(&path_prefix) {
+                                Some(path) => path,
+                                None => continue,
+                            };
+
+                            if path.is_empty()
+                                || path.ends_with('/')
+                                || path.contains("modinfo.json")
+                            {
+                                continue;
+                            }
+
+                            hashes.insert(path.to_owned(), format!("{:x}", hasher.finalize()));
+                        }
+                    }
+                    ArchiveContents::Err(err) => return Err(err.into()),
+                }
             }
 
-            Ok((files, dirs))
+            Ok(hashes)
         })
         .await?
     }
+
+    /// Gets all paths of the files and dirs contained in an archive. Automatically removes the modinfo.json and CHANGELOG.md paths if they exist as they do not need to be injected into the game.
+    ///
+    /// The path prefix is stripped from all paths and entries outside of the prefix are skipped.
+    pub async fn get_archive_dirs_and_files(
+        &self,
+        path_prefix: &str,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let path = self.path.clone();
+        let path_prefix = path_prefix.to_owned();
+        tauri::async_runtime::spawn_blocking(move || {
+            let archive = File::open(path)?;
+            let entries = compress_tools::list_archive_files(archive)?;
+
+            Ok(split_dirs_and_files(&entries, &path_prefix))
+        })
+        .await?
+    }
+}
+
+/// Finds the entry of the file with the provided name (case insensitive) which is closest to the base of the mod source
+pub(super) fn find_file<'a>(entries: &'a [String], file_name: &str) -> Option<&'a String> {
+    let file_name = file_name.to_lowercase();
+
+    entries
+        .iter()
+        .filter(|path| {
+            let path = path.to_lowercase();
+            path == file_name || path.ends_with(&format!("/{}", file_name))
+        })
+        .min_by_key(|path| path.matches('/').count())
+}
+
+/// Splits the entries of a mod source into the files and dirs which are injected into the game. Entries of dirs end with a slash.
+///
+/// The path prefix is stripped from all paths and entries outside of the prefix are skipped. The modinfo.json file and the CHANGELOG.md file read as changelog of the mod are removed as they do not need to be injected into the game.
+pub(super) fn split_dirs_and_files(
+    entries: &[String],
+    path_prefix: &str,
+) -> (Vec<String>, Vec<String>) {
+    let changelog = find_file(entries, CHANGELOG_FILE);
+    let mut files = vec![];
+    let mut dirs = vec![];
+
+    for path in entries
+        .iter()
+        .filter(|path| Some(*path) != changelog)
+        .filter_map(|path| path.strip_prefix(path_prefix))
+        .filter(|path| !path.is_empty())
+    {
+        if path.ends_with('/') {
+            dirs.push(path.to_owned());
+        } else if !path.contains("modinfo.json") {
+            files.push(path.to_owned());
+        }
+    }
+
+    (files, dirs)
+}
+
+/// The difference of the mod files between two versions of a mod
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FileDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Files which exist in both versions but have a different content
+    pub changed: Vec<String>,
+}
+
+impl FileDiff {
+    /// Computes the difference between the old and new files. Files without a known hash are treated as changed.
+    pub fn between(
+        old_files: &[String],
+        new_files: &[String],
+        old_hashes: &HashMap<String, String>,
+        new_hashes: &HashMap<String, String>,
+    ) -> Self {
+        let old_set: HashSet<&String> = old_files.iter().collect();
+        let new_set: HashSet<&String> = new_files.iter().collect();
+        let mut diff = Self::default();
+
+        for file in new_files {
+            if !old_set.contains(file) {
+                diff.added.push(file.to_owned());
+                continue;
+            }
+
+            match (old_hashes.get(file), new_hashes.get(file)) {
+                (Some(old_hash), Some(new_hash)) if old_hash == new_hash => (),
+                _ => diff.changed.push(file.to_owned()),
+            }
+        }
+
+        diff.removed = old_files
+            .iter()
+            .filter(|file| !new_set.contains(file))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    /// Computes the difference between all files of two archives
+    pub fn from_hashes(
+        old_hashes: &HashMap<String, String>,
+        new_hashes: &HashMap<String, String>,
+    ) -> Self {
+        let old_files: Vec<String> = old_hashes.keys().cloned().collect();
+        let new_files: Vec<String> = new_hashes.keys().cloned().collect();
+
+        Self::between(&old_files, &new_files, old_hashes, new_hashes)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// The number of added, removed and changed files
+    pub fn summary(&self) -> FileDiffSummary {
+        FileDiffSummary {
+            added: self.added.len(),
+            removed: self.removed.len(),
+            changed: self.changed.len(),
+        }
+    }
+}
+
+/// The number of files affected by a [`FileDiff`]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileDiffSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+/// Calculates the SHA-256 hash of a file as lowercase hex string
+pub(super) fn hash_file<P: AsRef<Path>>(path: P) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
 }
 
+/// The newest modinfo.json schema version supported by this modloader
+pub const MODINFO_SCHEMA_VERSION: u32 = 2;
+
 /// The modinfo.json file definition which contains additional information of a mod used by this app
+///
+/// Files without `schema_version` use schema version 1, which only knows the fields up to `changelog`. The extended metadata fields and translated names and infos have been added in schema version 2.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ModInfo {
-    pub name: String,
+    /// Version of the modinfo.json schema the file follows, None for schema version 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    /// Stable identifier of the mod, e.g. `author.modname`. Used to recognize the mod across renames and updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Name of the mod, either a single name or translations keyed by language code
+    pub name: LocalizedText,
     pub author: String,
     pub version: String,
-    pub info: String,
+    /// Description of the mod, either a single text or translations keyed by language code
+    pub info: LocalizedText,
     /// Type of mod injection that is required to install this mod
     pub injection: InjectionType,
+    /// Language codes of the game languages the mod is injected into. Used by language agnostic mods, defaults to the game language of the installation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub languages: Option<Vec<String>>,
+    /// Release notes of the mod versions, mapping the version to its notes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changelog: Option<HashMap<String, String>>,
+    /// Short summary of the mod, while `info` contains the full description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// URL of the website of the mod
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    /// License the mod is distributed under, preferably as SPDX identifier like `CC-BY-4.0`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Tags used to categorize the mod
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Oldest modloader version the mod works with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_modloader_version: Option<String>,
+    /// Language codes of the game languages the mod has been made for. Unlike `languages` this does not change where the mod is injected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supported_languages: Option<Vec<String>>,
+    /// Path of the preview image inside the mod, relative to the modinfo.json. If omitted a `preview.png` or `preview.jpg` file is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+    /// Groups of alternatives of which the user selects one on activation, each choice mapping to a folder inside the mod
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<OptionGroup>>,
+    /// Rules placing the files matching a glob pattern into a folder of the game, so the mod does not have to follow the folder layout of the game
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<FileRule>>,
+}
+
+impl ModInfo {
+    /// Deserialize the content of a modinfo.json file
+    ///
+    /// The file is linted first, so all problems of the file are reported at once with their position.
+    pub fn parse(file: &[u8]) -> Result<Self> {
+        let diagnostics = lint::lint(&String::from_utf8_lossy(file));
+
+        for warning in diagnostics
+            .iter()
+            .filter(|diagnostic| !diagnostic.is_error())
+        {
+            log::warn!("Problem in modinfo.json: {}", warning);
+        }
+
+        let errors: Vec<String> = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .map(ToString::to_string)
+            .collect();
+
+        if !errors.is_empty() {
+            return Err(ModManagerError::InvalidModInfo {
+                msg: errors.join("\n"),
+            });
+        }
+
+        serde_json::from_slice::<Self>(file)
+            .map_err(|e| ModManagerError::InvalidModInfo { msg: e.to_string() })
+    }
+
+    /// Checks the values which are not validated by deserializing: the name and id must not be empty, the versions have to follow semver, the languages have to be supported by the game and the file rules must not leave the mod or the game
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_blank() {
+            return Err(ModManagerError::InvalidModInfo {
+                msg: String::from("The mod name must not be empty"),
+            });
+        }
+
+        if matches!(&self.id, Some(id) if id.trim().is_empty()) {
+            return Err(ModManagerError::InvalidModInfo {
+                msg: String::from("The mod id must not be empty"),
+            });
+        }
+
+        Version::parse(&self.version)
+            .map_err(|e| ModManagerError::InvalidModInfo { msg: e.to_string() })?;
+
+        if let Some(version) = &self.min_modloader_version {
+            Version::parse(version)
+                .map_err(|e| ModManagerError::InvalidModInfo { msg: e.to_string() })?;
+        }
+
+        for language in self
+            .languages
+            .iter()
+            .chain(self.supported_languages.iter())
+            .flatten()
+        {
+            config::game_language_from_code(language).map_err(|_| {
+                ModManagerError::InvalidModInfo {
+                    msg: format!("Unsupported game language '{}'", language),
+                }
+            })?;
+        }
+
+        for rule in self.files.iter().flatten() {
+            rule.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashSet};
+
+    use super::*;
+    use crate::modmanager::mapping::FileMapping;
+
+    /// Files of a mod inside the game, as they are checked for conflicts on activation
+    fn injected_files(entries: &[&str], path_prefix: &str) -> Vec<String> {
+        let entries: Vec<String> = entries.iter().map(|entry| entry.to_string()).collect();
+        let (files, _) = split_dirs_and_files(&entries, path_prefix);
+
+        FileMapping::new(&files, None, &[], &BTreeMap::new(), &[]).files()
+    }
+
+    #[test]
+    fn mods_with_changelog_do_not_conflict() {
+        let first_mod = injected_files(
+            &[
+                "CHANGELOG.md",
+                "modinfo.json",
+                "localization/",
+                "localization/units/",
+                "localization/units/tank.xml",
+            ],
+            "",
+        );
+        let second_mod = injected_files(
+            &[
+                "Second Mod/",
+                "Second Mod/changelog.md",
+                "Second Mod/modinfo.json",
+                "Second Mod/localization/",
+                "Second Mod/localization/units/",
+                "Second Mod/localization/units/plane.xml",
+            ],
+            "Second Mod/",
+        );
+
+        assert_eq!(first_mod, vec!["localization/units/tank.xml"]);
+        assert_eq!(second_mod, vec!["localization/units/plane.xml"]);
+
+        let first_files: HashSet<&String> = first_mod.iter().collect();
+        assert!(second_mod.iter().all(|file| !first_files.contains(file)));
+    }
+
+    #[test]
+    fn only_the_changelog_of_the_mod_is_skipped() {
+        let files = injected_files(
+            &["CHANGELOG.md", "localization/", "localization/CHANGELOG.md"],
+            "",
+        );
+
+        assert_eq!(files, vec!["localization/CHANGELOG.md"]);
+    }
 }
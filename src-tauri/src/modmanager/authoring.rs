@@ -0,0 +1,122 @@
+//! Packaging of mod folders into archives which follow the required mod archive structure, used by mod authors
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::archive::{ModInfo, MODINFO_SCHEMA_VERSION};
+use super::error::{ModManagerError, Result};
+use super::injection::InjectionType;
+use super::layout;
+
+/// Metadata of a packaged mod. Provided values replace the ones of the modinfo.json file of the mod folder.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PackageMetadata {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    pub info: Option<String>,
+    /// Language codes of the game languages the mod is injected into
+    pub languages: Option<Vec<String>>,
+}
+
+impl PackageMetadata {
+    /// Builds the modinfo.json of the packaged mod from the metadata and the modinfo.json file of the folder if it exists
+    pub fn into_mod_info(self, existing: Option<ModInfo>) -> Result<ModInfo> {
+        let mut mod_info = match existing {
+            Some(mod_info) => mod_info,
+            None => ModInfo {
+                schema_version: Some(MODINFO_SCHEMA_VERSION),
+                id: None,
+                name: self
+                    .name
+                    .clone()
+                    .ok_or_else(|| missing_field("name"))?
+                    .into(),
+                author: self.author.clone().ok_or_else(|| missing_field("author"))?,
+                version: self
+                    .version
+                    .clone()
+                    .ok_or_else(|| missing_field("version"))?,
+                info: String::new().into(),
+                injection: InjectionType::Localization,
+                languages: None,
+                changelog: None,
+                description: None,
+                homepage: None,
+                license: None,
+                tags: None,
+                min_modloader_version: None,
+                supported_languages: None,
+                preview: None,
+                options: None,
+                files: None,
+            },
+        };
+
+        if self.id.is_some() {
+            mod_info.id = self.id;
+        }
+
+        // Translations of the name and info are kept, only the default text is replaced
+        if let Some(name) = self.name {
+            mod_info.name.set_default(name);
+        }
+
+        if let Some(author) = self.author {
+            mod_info.author = author;
+        }
+
+        if let Some(version) = self.version {
+            mod_info.version = version;
+        }
+
+        if let Some(info) = self.info {
+            mod_info.info.set_default(info);
+        }
+
+        if self.languages.is_some() {
+            mod_info.languages = self.languages;
+        }
+
+        Ok(mod_info)
+    }
+}
+
+fn missing_field(field: &str) -> ModManagerError {
+    ModManagerError::InvalidModInfo {
+        msg: format!(
+            "The folder does not contain a modinfo.json file and no mod {} has been provided",
+            field
+        ),
+    }
+}
+
+/// Result of packaging a mod folder
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PackageReport {
+    /// Prefix which has been stripped from the folder paths because the layout of the folder was misplaced
+    pub path_prefix: Option<String>,
+    /// Top level folders of the mod which do not exist in the localization folder of the game. These are most likely a mistake in the layout.
+    pub unknown_folders: Vec<String>,
+}
+
+/// Finds the top level folders of the mod files which do not exist in the localization folder of the game
+///
+/// If no game localization folder is available the folders are compared against the folders known to exist in the game.
+pub fn unknown_game_folders(files: &[String], localization_path: Option<&Path>) -> Vec<String> {
+    let folders: BTreeSet<&str> = files
+        .iter()
+        .filter_map(|file| file.split_once('/').map(|(folder, _)| folder))
+        .collect();
+
+    folders
+        .into_iter()
+        .filter(|folder| match localization_path {
+            Some(localization_path) => !localization_path.join(folder).is_dir(),
+            None => !layout::is_known_game_folder(folder),
+        })
+        .map(str::to_owned)
+        .collect()
+}
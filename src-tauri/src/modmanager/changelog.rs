@@ -0,0 +1,98 @@
+//! Changelogs of mods, provided either in the modinfo.json file or as CHANGELOG.md file inside the mod archive
+use std::collections::{BTreeMap, HashMap};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use super::error::{ModManagerError, Result};
+
+/// The changelog of a mod with the release notes of each version
+#[derive(Debug, Default)]
+pub struct Changelog(BTreeMap<Version, String>);
+
+/// The release notes of a single mod version
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub notes: String,
+}
+
+impl Changelog {
+    /// Create the changelog from the version to notes map of a modinfo.json file
+    pub fn from_map(map: HashMap<String, String>) -> Result<Self> {
+        let mut changelog = BTreeMap::new();
+
+        for (version, notes) in map {
+            let version =
+                Version::parse(&version).map_err(|e| ModManagerError::InvalidModInfo {
+                    msg: format!("Invalid changelog version '{}': {}", version, e),
+                })?;
+
+            changelog.insert(version, notes);
+        }
+
+        Ok(Self(changelog))
+    }
+
+    /// Parse a CHANGELOG.md file. Every heading containing a semver version starts the notes of that version, e.g. `## [1.2.0] - 2022-12-01`.
+    ///
+    /// Text which does not belong to a version heading is ignored.
+    pub fn from_markdown(markdown: &str) -> Self {
+        let mut changelog = BTreeMap::new();
+        let mut current: Option<(Version, Vec<&str>)> = None;
+
+        for line in markdown.lines() {
+            if line.trim_start().starts_with('#') {
+                if let Some(version) = heading_version(line) {
+                    if let Some((version, notes)) = current.take() {
+                        changelog.insert(version, notes.join("\n").trim().to_owned());
+                    }
+
+                    current = Some((version, vec![]));
+                    continue;
+                }
+            }
+
+            if let Some((_, notes)) = current.as_mut() {
+                notes.push(line);
+            }
+        }
+
+        if let Some((version, notes)) = current.take() {
+            changelog.insert(version, notes.join("\n").trim().to_owned());
+        }
+
+        Self(changelog)
+    }
+
+    /// The entries relevant when switching from the installed to the new version, newest first
+    ///
+    /// For upgrades these are the versions after the installed version up to the new one. For downgrades the versions which are reverted are returned.
+    pub fn entries_between(&self, installed: &Version, new: &Version) -> Vec<ChangelogEntry> {
+        let (lower, upper) = if installed < new {
+            (installed, new)
+        } else {
+            (new, installed)
+        };
+
+        self.0
+            .iter()
+            .rev()
+            .filter(|(version, _)| *version > lower && *version <= upper)
+            .map(|(version, notes)| ChangelogEntry {
+                version: version.to_string(),
+                notes: notes.to_owned(),
+            })
+            .collect()
+    }
+}
+
+/// Find the first word of a markdown heading which is a semver version
+fn heading_version(line: &str) -> Option<Version> {
+    line.split_whitespace().find_map(|word| {
+        let word = word.trim_matches(|c: char| c == '#' || c == '[' || c == ']');
+        let word = word.strip_prefix('v').unwrap_or(word);
+
+        Version::parse(word).ok()
+    })
+}
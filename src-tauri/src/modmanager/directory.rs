@@ -0,0 +1,248 @@
+//! Functions to read mods from unpacked folders
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sevenz_rust::{SevenZArchiveEntry, SevenZWriter};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+use super::archive::{self, InvalidArchive, ModInfo};
+use super::error::{ModManagerError, Result};
+use super::layout;
+
+/// Intermediate Struct used to open an unpacked mod folder and read its contents
+#[derive(Clone)]
+pub struct ModDirectory {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl ModDirectory {
+    pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = dunce::canonicalize(path)?;
+
+        if !path.exists() {
+            return Err(ModManagerError::InvalidArchive(
+                InvalidArchive::PathNotExisting,
+            ));
+        }
+
+        if !path.is_dir() {
+            return Err(ModManagerError::InvalidArchive(
+                InvalidArchive::PathNotDirectory,
+            ));
+        }
+
+        let directory_name = path
+            .file_name()
+            .unwrap_or_else(|| OsStr::new("Unknown"))
+            .to_str()
+            .unwrap_or("Unknown")
+            .to_owned();
+
+        Ok(Self {
+            name: directory_name,
+            path,
+        })
+    }
+
+    pub async fn get_modinfo(&self) -> Result<Option<ModInfo>> {
+        let mod_info_file = match self.get_modinfo_file().await? {
+            Some(mod_info_file) => mod_info_file,
+            None => return Ok(None),
+        };
+
+        log::debug!("Found modinfo file, trying to deserialize...");
+
+        ModInfo::parse(&mod_info_file).map(Some)
+    }
+
+    /// Reads the raw content of the modinfo.json file of the folder if it exists
+    pub async fn get_modinfo_file(&self) -> Result<Option<Vec<u8>>> {
+        self.read_file("modinfo.json").await
+    }
+
+    /// Reads the CHANGELOG.md file of the folder if it exists
+    pub async fn get_changelog_file(&self) -> Result<Option<String>> {
+        Ok(self
+            .read_file(archive::CHANGELOG_FILE)
+            .await?
+            .map(|changelog| String::from_utf8_lossy(&changelog).to_string()))
+    }
+
+    /// Reads the file with the provided name (case insensitive) which is closest to the base of the folder
+    async fn read_file(&self, file_name: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path.clone();
+        let file_name = file_name.to_owned();
+        tauri::async_runtime::spawn_blocking(move || {
+            let entries = list_entries(&path)?;
+
+            match archive::find_file(&entries, &file_name) {
+                Some(entry) => Ok(Some(fs::read(path.join(entry))?)),
+                None => Ok(None),
+            }
+        })
+        .await?
+    }
+
+    /// Detects common mistakes in the folder layout. Returns the prefix which has to be stripped from the paths to get the paths inside the game.
+    ///
+    /// If the files of the mod are placed by file rules, the prefix is the folder of the modinfo.json instead.
+    pub async fn detect_layout_prefix(
+        &self,
+        has_file_rules: bool,
+        localization_path: Option<&Path>,
+    ) -> Result<Option<String>> {
+        let path = self.path.clone();
+        let localization_path = localization_path.map(Path::to_path_buf);
+        tauri::async_runtime::spawn_blocking(move || {
+            let entries = list_entries(&path)?;
+
+            if has_file_rules {
+                return Ok(layout::modinfo_prefix(&entries));
+            }
+
+            layout::detect_prefix(&entries, localization_path.as_deref())
+        })
+        .await?
+    }
+
+    /// Calculates the SHA-256 hash of every mod file in the folder, keyed by the path inside the game. The modinfo.json and CHANGELOG.md files and files outside of the path prefix are skipped.
+    pub async fn get_file_hashes(&self, path_prefix: &str) -> Result<HashMap<String, String>> {
+        let path = self.path.clone();
+        let path_prefix = path_prefix.to_owned();
+        tauri::async_runtime::spawn_blocking::<_, Result<HashMap<String, String>>>(move || {
+            let entries = list_entries(&path)?;
+            let (files, _) = archive::split_dirs_and_files(&entries, &path_prefix);
+            let mut hashes = HashMap::new();
+
+            for file in files {
+                let hash = archive::hash_file(path.join(format!("{}{}", path_prefix, file)))?;
+                hashes.insert(file, hash);
+            }
+
+            Ok(hashes)
+        })
+        .await?
+    }
+
+    /// Gets all paths of the files and dirs contained in the folder, relative to the folder. The modinfo.json and CHANGELOG.md files are skipped.
+    ///
+    /// The path prefix is stripped from all paths and entries outside of the prefix are skipped.
+    pub async fn get_dirs_and_files(
+        &self,
+        path_prefix: &str,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let path = self.path.clone();
+        let path_prefix = path_prefix.to_owned();
+        tauri::async_runtime::spawn_blocking(move || {
+            Ok(archive::split_dirs_and_files(
+                &list_entries(&path)?,
+                &path_prefix,
+            ))
+        })
+        .await?
+    }
+
+    /// Packs the content of the folder into a zip archive at the provided path
+    ///
+    /// All entries get the same timestamp, so packing an unchanged folder again results in an identical archive.
+    pub async fn pack(&self, archive_path: &Path) -> Result<()> {
+        let path = self.path.clone();
+        let archive_path = archive_path.to_owned();
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut zip = ZipWriter::new(File::create(archive_path)?);
+            let options = FileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .last_modified_time(DateTime::default());
+
+            for entry in list_entries(&path)? {
+                if entry.ends_with('/') {
+                    zip.add_directory(entry.as_str(), options)?;
+                } else {
+                    zip.start_file(entry.as_str(), options)?;
+                    io::copy(&mut File::open(path.join(&entry))?, &mut zip)?;
+                }
+            }
+
+            zip.finish()?;
+
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Packs the content of the folder into a 7z archive at the provided path
+    pub async fn pack_7z(&self, archive_path: &Path) -> Result<()> {
+        let path = self.path.clone();
+        let archive_path = archive_path.to_owned();
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut writer = SevenZWriter::create(archive_path)?;
+
+            for entry in list_entries(&path)? {
+                let entry_path = path.join(&entry);
+                let name = entry.trim_end_matches('/').to_owned();
+
+                if entry.ends_with('/') {
+                    writer.push_archive_entry::<File>(
+                        SevenZArchiveEntry::from_path(entry_path, name),
+                        None,
+                    )?;
+                } else {
+                    writer.push_archive_entry(
+                        SevenZArchiveEntry::from_path(&entry_path, name),
+                        Some(File::open(&entry_path)?),
+                    )?;
+                }
+            }
+
+            writer.finish()?;
+
+            Ok(())
+        })
+        .await?
+    }
+}
+
+/// Lists all files and dirs inside the folder recursively, in the same form as archive entries: relative to the folder, separated by slashes and dirs ending with a slash. The entries are sorted, so dirs are listed before their content.
+///
+/// Symlinked dirs are skipped, as they can point outside of the folder or into one of its parents.
+fn list_entries(root: &Path) -> Result<Vec<String>> {
+    let mut entries = vec![];
+    let mut pending_dirs = vec![root.to_owned()];
+
+    while let Some(dir) = pending_dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+
+            if file_type.is_symlink() && path.is_dir() {
+                log::warn!("Skipping symlinked folder {:?} of mod folder", path);
+                continue;
+            }
+
+            let relative_path = path
+                .strip_prefix(root)
+                .expect("Directory entry is not located inside the mod folder")
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if file_type.is_dir() {
+                entries.push(format!("{}/", relative_path));
+                pending_dirs.push(path);
+            } else {
+                entries.push(relative_path);
+            }
+        }
+    }
+
+    entries.sort();
+
+    Ok(entries)
+}
@@ -25,17 +25,38 @@ pub enum ModManagerError {
     ArchiveHandling {
         msg: String,
     },
+    /// The archive of the mod in the registry is missing or does not match its hash anymore
+    ArchiveCorrupted {
+        name: String,
+    },
     /// Errors happening while parsing a potential modinfo.json file
     InvalidModInfo {
         msg: String,
     },
+    /// Errors happening while reading the modpack.json manifest of a mod pack
+    InvalidPackManifest {
+        msg: String,
+    },
+    /// The mod requires a newer version of the modloader
+    ModloaderOutdated {
+        required: String,
+    },
     ModNotExisting,
+    /// The requested version of the mod does not exist in the registry
+    ModVersionNotExisting,
+    /// The selected choice of an option group does not exist in the mod
+    ModOptionNotExisting {
+        group: String,
+        choice: String,
+    },
+    /// The selected options can not be changed, as the mod is injected with its current options into other game installations
+    ModOptionsInUse {
+        installations: Vec<String>,
+    },
     ModAlreadyActive,
     ModAlreadyDeactivated,
-    /// If the mod being added has an older version than the identical mod already existing in the registry
-    ModVersionMismatch {
-        mismatch: (String, String),
-    },
+    /// The mod is not a development mod which is read from a folder
+    NotADevelopmentMod,
     /// The initial configuration data required for the modloader has not been provided
     AppNotInitialized,
     /// If the mod conflicts with other mods eg. modifies/uses the same files. The returned tuple contains the conflicting mod name and the conflicting file path
@@ -43,6 +64,10 @@ pub enum ModManagerError {
         conflict: Vec<(String, String)>,
     },
     ConfigError(ConfigError),
+    /// The localization folder of a target language of the mod does not exist in the game installation
+    LanguageNotInstalled {
+        language: String,
+    },
     TauriError {
         msg: String,
     },
@@ -105,6 +130,51 @@ impl From<compress_tools::Error> for ModManagerError {
     }
 }
 
+impl From<zip::result::ZipError> for ModManagerError {
+    fn from(error: zip::result::ZipError) -> Self {
+        match error {
+            zip::result::ZipError::Io(error) => Self::Io {
+                msg: error.to_string(),
+            },
+            _ => Self::ArchiveHandling {
+                msg: error.to_string(),
+            },
+        }
+    }
+}
+
+impl From<image::ImageError> for ModManagerError {
+    fn from(error: image::ImageError) -> Self {
+        Self::DeSerialization {
+            msg: error.to_string(),
+        }
+    }
+}
+
+impl From<sevenz_rust::Error> for ModManagerError {
+    fn from(error: sevenz_rust::Error) -> Self {
+        Self::ArchiveHandling {
+            msg: error.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ModManagerError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::DeSerialization {
+            msg: error.to_string(),
+        }
+    }
+}
+
+impl From<notify::Error> for ModManagerError {
+    fn from(error: notify::Error) -> Self {
+        Self::Io {
+            msg: format!("Failed to watch folder: {}", error),
+        }
+    }
+}
+
 impl From<ConfigError> for ModManagerError {
     fn from(error: ConfigError) -> Self {
         Self::ConfigError(error)
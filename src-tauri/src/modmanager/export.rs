@@ -0,0 +1,161 @@
+//! Export of registered mods as redistributable archives
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::archive::{ModInfo, CHANGELOG_FILE};
+use super::directory::ModDirectory;
+use super::error::Result;
+use super::registry::Mod;
+use super::source::ModSource;
+
+/// Characters which are not allowed in file names on Windows
+const INVALID_FILE_NAME_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Device names which can not be used as file names on Windows, even if followed by an extension
+const RESERVED_FILE_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// The archive formats mods can be exported to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Zip,
+    SevenZip,
+}
+
+/// Writes the files of the current version of the mod into the target folder, together with a modinfo.json file describing the mod
+///
+/// The layout of the files is fixed, so they are placed in the base of the folder. Mods which have been added without modinfo.json are exported without one as well.
+pub async fn write_mod(modification: &Mod, target_dir: &Path) -> Result<()> {
+    let mod_source = modification.open_source().await?;
+
+    // The changelog is not stored in the registry, so it is taken over from the original modinfo.json
+    let changelog = mod_source
+        .get_modinfo()
+        .await?
+        .and_then(|mod_info| mod_info.changelog);
+    let mod_info = modification.to_mod_info(changelog);
+
+    write_source(mod_source, modification.path_prefix(), mod_info, target_dir).await
+}
+
+/// Writes the files of the source inside the path prefix into the base of the target folder, together with the modinfo.json file if provided
+///
+/// The CHANGELOG.md file is not part of the mod files, so it is written into the base of the folder separately.
+pub async fn write_source(
+    mod_source: ModSource,
+    path_prefix: &str,
+    mod_info: Option<ModInfo>,
+    target_dir: &Path,
+) -> Result<()> {
+    let (files, dirs) = mod_source.get_dirs_and_files(path_prefix).await?;
+    let changelog = mod_source.get_changelog_file().await?;
+
+    let path_prefix = path_prefix.to_owned();
+    let target_dir = target_dir.to_owned();
+    tauri::async_runtime::spawn_blocking(move || {
+        let reader = mod_source.reader()?;
+
+        fs::create_dir_all(&target_dir)?;
+
+        for dir in dirs {
+            fs::create_dir_all(target_dir.join(dir))?;
+        }
+
+        for file in files {
+            let target_path = target_dir.join(&file);
+
+            if let Some(parent) = target_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            reader.extract(
+                &format!("{}{}", path_prefix, file),
+                &File::create(target_path)?,
+            )?;
+        }
+
+        if let Some(mod_info) = mod_info {
+            fs::write(
+                target_dir.join("modinfo.json"),
+                serde_json::to_vec_pretty(&mod_info)?,
+            )?;
+        }
+
+        if let Some(changelog) = changelog {
+            fs::write(target_dir.join(CHANGELOG_FILE), changelog)?;
+        }
+
+        Ok(())
+    })
+    .await?
+}
+
+/// Packs the content of the folder into an archive of the provided format
+pub async fn pack_folder(
+    folder_path: &Path,
+    format: ExportFormat,
+    archive_path: &Path,
+) -> Result<()> {
+    let directory = ModDirectory::open(folder_path).await?;
+
+    match format {
+        ExportFormat::Zip => directory.pack(archive_path).await,
+        ExportFormat::SevenZip => directory.pack_7z(archive_path).await,
+    }
+}
+
+/// Names the folders of the mods inside an exported mod pack after the mods. Characters and names which are not allowed in file names are replaced and duplicate names are numbered.
+pub fn pack_folder_names(mods: &[Mod]) -> Vec<String> {
+    let mut used_names = HashSet::new();
+
+    mods.iter()
+        .map(|modification| {
+            let name: String = modification
+                .name
+                .chars()
+                .map(|character| {
+                    if INVALID_FILE_NAME_CHARS.contains(&character) || character.is_control() {
+                        '_'
+                    } else {
+                        character
+                    }
+                })
+                .collect();
+
+            let name = match name.trim().trim_end_matches('.') {
+                "" => String::from("Mod"),
+                name => avoid_reserved_name(name),
+            };
+
+            let mut folder_name = name.clone();
+            let mut number = 1;
+
+            // Folder names are case insensitive on Windows
+            while !used_names.insert(folder_name.to_lowercase()) {
+                number += 1;
+                folder_name = format!("{} ({})", name, number);
+            }
+
+            folder_name
+        })
+        .collect()
+}
+
+/// Appends an underscore to a name reserved on Windows, e.g. "CON" becomes "CON_" and "nul.txt" becomes "nul_.txt"
+fn avoid_reserved_name(name: &str) -> String {
+    let (stem, extension) = name.split_at(name.find('.').unwrap_or(name.len()));
+
+    if RESERVED_FILE_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem.trim_end()))
+    {
+        format!("{}_{}", stem, extension)
+    } else {
+        name.to_owned()
+    }
+}
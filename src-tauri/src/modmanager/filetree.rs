@@ -1,15 +1,19 @@
 //! The file tree is used to keep track on which files have been modified/created by which mods.
 //! This allows to check whether mods collide with each other. And allows efficient file cleanup/restore on mod deactivation/deletion
+//!
+//! Each localization language of a game installation has its own file tree, as the same mod can be active in one installation and inactive in another.
+//! Keeping the languages apart allows the same file path to be owned by different mods in different languages.
 use std::vec;
 
 use serde::{Deserialize, Serialize};
 use sled::transaction::{TransactionError, UnabortableTransactionError};
+use sled::Tree;
 
 use super::error::{ModManagerError, Result};
 use crate::db::{BincodeTransactional, Key};
 use crate::DB;
 
-const DB_FILE_TREE_NAME: &str = "filetree";
+pub(super) const DB_FILE_TREE_NAME: &str = "filetree";
 
 /// A single Tree file entry which contains the UID of the mod that owns this file
 #[derive(Serialize, Deserialize)]
@@ -18,10 +22,53 @@ struct TreeFileEntry(u64);
 pub struct FileTreeManager;
 
 impl FileTreeManager {
+    /// Opens the file tree of the provided game installation and language
+    fn open_tree(installation: &str, language: &str) -> Tree {
+        DB.open_tree(&Self::tree_name(installation, language))
+    }
+
+    pub(super) fn tree_name(installation: &str, language: &str) -> String {
+        format!("{}{}", Self::installation_prefix(installation), language)
+    }
+
+    fn installation_prefix(installation: &str) -> String {
+        format!("{}/{}/", DB_FILE_TREE_NAME, installation)
+    }
+
+    /// Get all languages of the provided game installation which have a file tree
+    pub fn get_languages(installation: &str) -> Vec<String> {
+        let prefix = Self::installation_prefix(installation);
+
+        DB.get_inner()
+            .tree_names()
+            .into_iter()
+            .filter_map(|name| {
+                String::from_utf8(name.to_vec())
+                    .ok()?
+                    .strip_prefix(&prefix)
+                    .map(|language| language.to_owned())
+            })
+            .collect()
+    }
+
+    /// Removes the file trees of all languages of the provided game installation
+    pub fn drop_trees(installation: &str) -> Result<()> {
+        for language in Self::get_languages(installation) {
+            DB.get_inner()
+                .drop_tree(Self::tree_name(installation, &language))?;
+        }
+
+        Ok(())
+    }
+
     /// Checks if the provided list of file paths does not conflict with any existing file tree entries.
     /// Returns None if no conflicts are found and a vector containing the conflicting mod uid's with the corresponding conflicting path.
-    pub fn get_conflicts(file_paths: &Vec<String>) -> Result<Option<Vec<(u64, String)>>> {
-        let tree = DB.open_tree(DB_FILE_TREE_NAME);
+    pub fn get_conflicts(
+        installation: &str,
+        language: &str,
+        file_paths: &Vec<String>,
+    ) -> Result<Option<Vec<(u64, String)>>> {
+        let tree = Self::open_tree(installation, language);
 
         let conflicts = tree
             .transaction::<_, _, UnabortableTransactionError>(|transaction| {
@@ -54,8 +101,13 @@ impl FileTreeManager {
     }
 
     /// Insert the list of file paths into the file tree with the corresponding mod uid
-    pub fn insert_files(uid: u64, file_paths: &Vec<String>) -> Result<()> {
-        let tree = DB.open_tree(DB_FILE_TREE_NAME);
+    pub fn insert_files(
+        installation: &str,
+        language: &str,
+        uid: u64,
+        file_paths: &Vec<String>,
+    ) -> Result<()> {
+        let tree = Self::open_tree(installation, language);
 
         tree.transaction::<_, _, UnabortableTransactionError>(|transaction| {
             for file_path in file_paths {
@@ -77,8 +129,8 @@ impl FileTreeManager {
     }
 
     /// Get all files in the tree owned by the provided mod uid
-    pub fn get_files(uid: u64) -> Result<Vec<String>> {
-        let tree = DB.open_tree(DB_FILE_TREE_NAME);
+    pub fn get_files(installation: &str, language: &str, uid: u64) -> Result<Vec<String>> {
+        let tree = Self::open_tree(installation, language);
 
         let mut found_paths = vec![];
 
@@ -103,8 +155,8 @@ impl FileTreeManager {
     }
 
     /// Remove all provided file paths from the tree
-    pub fn remove_files(paths: &Vec<String>) -> Result<()> {
-        let tree = DB.open_tree(DB_FILE_TREE_NAME);
+    pub fn remove_files(installation: &str, language: &str, paths: &Vec<String>) -> Result<()> {
+        let tree = Self::open_tree(installation, language);
 
         tree.transaction::<_, _, UnabortableTransactionError>(|transaction| {
             for file_path in paths {
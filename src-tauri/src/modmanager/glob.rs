@@ -0,0 +1,158 @@
+//! Glob patterns matching the paths of files inside a mod
+//!
+//! Supports `*` and `?` inside of a path component and `**` for any number of components. Like the file system of the game, matching ignores the case.
+
+/// A glob pattern relative to the path prefix of the mod
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    components: Vec<Vec<char>>,
+    /// Number of leading components without wildcards, which are stripped from matched paths
+    base_len: usize,
+}
+
+impl Pattern {
+    /// Parses the pattern. A trailing slash matches the whole content of the folder, like `folder/**`.
+    pub fn new(pattern: &str) -> Self {
+        let mut pattern = pattern.replace('\\', "/");
+
+        if pattern.ends_with('/') {
+            pattern.push_str("**");
+        }
+
+        let components: Vec<Vec<char>> = pattern
+            .split('/')
+            .filter(|component| !component.is_empty() && *component != ".")
+            .map(|component| component.to_lowercase().chars().collect())
+            .collect();
+
+        let literal_len = components
+            .iter()
+            .take_while(|component| !component.iter().any(|c| matches!(c, '*' | '?')))
+            .count();
+
+        // A pattern of a single file keeps the file name
+        let base_len = literal_len.min(components.len().saturating_sub(1));
+
+        Self {
+            components,
+            base_len,
+        }
+    }
+
+    /// Whether the path of a file inside the mod matches the pattern
+    pub fn matches(&self, path: &str) -> bool {
+        let path = path.to_lowercase();
+        let path: Vec<Vec<char>> = path
+            .split('/')
+            .map(|component| component.chars().collect())
+            .collect();
+
+        match_components(&self.components, &path)
+    }
+
+    /// Strips the literal base of the pattern from a matching path, so the remaining path can be placed into another folder
+    pub fn strip_base<'a>(&self, path: &'a str) -> &'a str {
+        if self.base_len == 0 {
+            return path;
+        }
+
+        path.match_indices('/')
+            .nth(self.base_len - 1)
+            .map_or(path, |(index, _)| &path[index + 1..])
+    }
+}
+
+fn match_components(pattern: &[Vec<char>], path: &[Vec<char>]) -> bool {
+    match_wildcards(
+        pattern,
+        path,
+        |component| component.as_slice() == ['*', '*'],
+        |component, path_component| match_component(component, path_component),
+    )
+}
+
+fn match_component(pattern: &[char], text: &[char]) -> bool {
+    match_wildcards(
+        pattern,
+        text,
+        |c| *c == '*',
+        |c, text_c| *c == '?' || c == text_c,
+    )
+}
+
+/// Matches a sequence against a pattern in which stars match any number of items
+///
+/// Only the most recent star is backtracked to, as a later star can match everything an earlier one could. This keeps matching quadratic instead of exponential in the number of stars.
+fn match_wildcards<T>(
+    pattern: &[T],
+    text: &[T],
+    is_star: impl Fn(&T) -> bool,
+    matches_item: impl Fn(&T, &T) -> bool,
+) -> bool {
+    let mut pattern_idx = 0;
+    let mut text_idx = 0;
+    // Position of the last star in the pattern and the position in the text it has been matched up to
+    let mut last_star: Option<(usize, usize)> = None;
+
+    while text_idx < text.len() {
+        match pattern.get(pattern_idx) {
+            Some(item) if is_star(item) => {
+                last_star = Some((pattern_idx, text_idx));
+                pattern_idx += 1;
+            }
+            Some(item) if matches_item(item, &text[text_idx]) => {
+                pattern_idx += 1;
+                text_idx += 1;
+            }
+            _ => match last_star {
+                // Let the last star match one more item and retry the rest of the pattern
+                Some((star_idx, star_text_idx)) => {
+                    last_star = Some((star_idx, star_text_idx + 1));
+                    pattern_idx = star_idx + 1;
+                    text_idx = star_text_idx + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[pattern_idx..].iter().all(is_star)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_followed_by_repeated_literal() {
+        assert!(Pattern::new("a*aab").matches("aaaaaaab"));
+        assert!(Pattern::new("textures/*aa.dds").matches("textures/aaaaaa.dds"));
+        assert!(!Pattern::new("a*aab").matches("aaaaaaaa"));
+
+        // Would take exponential time if every star was backtracked to
+        let text = "a".repeat(100);
+        assert!(!Pattern::new(&"*a".repeat(20)).matches(&format!("{}b", text)));
+        assert!(
+            !Pattern::new(&format!("{}b", "**/a/".repeat(10))).matches(&vec!["a"; 50].join("/"))
+        );
+    }
+
+    #[test]
+    fn trailing_star() {
+        let pattern = Pattern::new("textures/*");
+
+        assert!(pattern.matches("textures/tank.dds"));
+        assert!(pattern.matches("Textures/Tank.DDS"));
+        assert!(!pattern.matches("textures/units/tank.dds"));
+        assert!(Pattern::new("tank*").matches("tank"));
+        assert!(Pattern::new("textures/").matches("textures/units/tank.dds"));
+    }
+
+    #[test]
+    fn no_match() {
+        assert!(!Pattern::new("objects/*.xml").matches("textures/tank.xml"));
+        assert!(!Pattern::new("objects/*.xml").matches("objects/tank.dds"));
+        assert!(!Pattern::new("objects/tank?.xml").matches("objects/tank.xml"));
+        assert!(!Pattern::new("objects/**/tank.xml").matches("objects/units/plane.xml"));
+    }
+}
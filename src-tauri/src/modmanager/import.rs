@@ -0,0 +1,183 @@
+//! Import of multiple mod archives at once
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use super::archive::ArchiveFormat;
+use super::error::{ModManagerError, Result};
+
+/// How mods which already exist in the registry are handled when adding mods
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Replace existing mods without asking
+    Always,
+    /// Keep existing mods and skip the added ones
+    Never,
+    /// Ask the user for every existing mod
+    Ask,
+    /// Ask the user for the first existing mod and apply the answer to all further ones
+    AskOnce,
+}
+
+/// Decides whether existing mods are overwritten according to an [`OverwritePolicy`]. Remembers the answer of the user for [`OverwritePolicy::AskOnce`].
+pub struct OverwriteResolver {
+    policy: OverwritePolicy,
+    answer: Mutex<Option<bool>>,
+}
+
+impl OverwriteResolver {
+    pub fn new(policy: OverwritePolicy) -> Self {
+        Self {
+            policy,
+            answer: Mutex::new(None),
+        }
+    }
+
+    /// The decision whether to overwrite an existing mod, None if the user has to be asked
+    pub fn decision(&self) -> Option<bool> {
+        match self.policy {
+            OverwritePolicy::Always => Some(true),
+            OverwritePolicy::Never => Some(false),
+            OverwritePolicy::Ask => None,
+            OverwritePolicy::AskOnce => *self
+                .answer
+                .lock()
+                .expect("Overwrite answer mutex has been poisoned"),
+        }
+    }
+
+    /// If the answer of the user is applied to all further existing mods
+    pub fn asks_once(&self) -> bool {
+        self.policy == OverwritePolicy::AskOnce
+    }
+
+    /// Remember the answer of the user for further existing mods
+    pub fn remember(&self, overwrite: bool) {
+        if self.asks_once() {
+            *self
+                .answer
+                .lock()
+                .expect("Overwrite answer mutex has been poisoned") = Some(overwrite);
+        }
+    }
+}
+
+/// A mod which has been added to the registry
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AddedMod {
+    pub uid: u64,
+    pub name: String,
+    /// If an existing mod has been replaced by the added one
+    pub updated: bool,
+    /// Prefix which is stripped from the archive paths if the archive layout had to be fixed
+    pub path_prefix: Option<String>,
+}
+
+/// Outcome of adding a single mod archive or folder
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum AddModResult {
+    /// The archive contains a single mod. The path prefix is stripped from the archive paths if the archive layout had to be fixed.
+    Mod { path_prefix: Option<String> },
+    /// The archive is a mod pack whose mods have been added individually
+    Pack(ImportReport),
+}
+
+/// An archive which has been added to the registry by an import
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportedArchive {
+    pub path: String,
+    pub uid: u64,
+    pub name: String,
+    pub path_prefix: Option<String>,
+}
+
+/// An archive which could not be imported
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FailedImport {
+    pub path: String,
+    pub error: ModManagerError,
+}
+
+/// Report of an import with the outcome of every archive
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ImportReport {
+    pub added: Vec<ImportedArchive>,
+    pub updated: Vec<ImportedArchive>,
+    /// Archives of mods which already exist and have not been overwritten
+    pub skipped: Vec<String>,
+    pub failed: Vec<FailedImport>,
+}
+
+impl ImportReport {
+    /// Add the outcome of importing the archive at the provided path to the report
+    pub fn record(&mut self, path: String, result: Result<Option<AddedMod>>) {
+        match result {
+            Ok(Some(added_mod)) => {
+                let imported_archive = ImportedArchive {
+                    path,
+                    uid: added_mod.uid,
+                    name: added_mod.name,
+                    path_prefix: added_mod.path_prefix,
+                };
+
+                if added_mod.updated {
+                    self.updated.push(imported_archive);
+                } else {
+                    self.added.push(imported_archive);
+                }
+            }
+            Ok(None) => self.skipped.push(path),
+            Err(error) => {
+                log::warn!("Failed to import mod archive {}: {:?}", path, error);
+
+                self.failed.push(FailedImport { path, error })
+            }
+        }
+    }
+}
+
+/// Resolves the provided paths to the archives which are imported. Folders are replaced by the archives they contain, subfolders and files which are not archives are ignored.
+///
+/// Other paths are kept as they are, so invalid paths are reported as failed imports. Folders which can not be read are returned as failed imports.
+pub fn collect_archive_paths(paths: Vec<String>) -> (Vec<String>, Vec<FailedImport>) {
+    let mut archive_paths = vec![];
+    let mut failed = vec![];
+
+    for path in paths {
+        if !Path::new(&path).is_dir() {
+            archive_paths.push(path);
+            continue;
+        }
+
+        match folder_archives(Path::new(&path)) {
+            Ok(folder_archives) => archive_paths.extend(folder_archives),
+            Err(error) => {
+                log::warn!("Failed to read mod folder {}: {:?}", path, error);
+
+                failed.push(FailedImport { path, error });
+            }
+        }
+    }
+
+    (archive_paths, failed)
+}
+
+/// Paths of the archives inside the folder, sorted by name
+fn folder_archives(folder: &Path) -> Result<Vec<String>> {
+    let mut archive_paths = vec![];
+
+    for entry in fs::read_dir(folder)? {
+        let entry_path = entry?.path();
+
+        if entry_path.is_file() && ArchiveFormat::detect(&entry_path).is_ok() {
+            archive_paths.push(entry_path.to_string_lossy().to_string());
+        }
+    }
+
+    archive_paths.sort();
+
+    Ok(archive_paths)
+}
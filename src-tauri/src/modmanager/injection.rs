@@ -3,12 +3,14 @@ use std::fs::{self, File};
 
 use serde::{Deserialize, Serialize};
 
-use crate::config::ModloaderConfig;
+use crate::config::GameInstallation;
 
-use super::archive::ModArchive;
+use super::archive::FileDiff;
 use super::error::{ModManagerError, Result};
 use super::filetree::FileTreeManager;
+use super::mapping::FileMapping;
 use super::registry::Mod;
+use super::source::ModSource;
 
 /// The different injection techniques used to install mods in AW.
 ///
@@ -30,97 +32,290 @@ impl From<&str> for InjectionType {
 }
 
 impl InjectionType {
+    /// Inject the mod files into every target language of the provided game installation and set the mod active in it
+    ///
+    /// If the injection fails, the files already injected into the other languages are removed again, so no files of an inactive mod are left in the game.
     pub async fn inject_mod(
         &self,
         mut modification: Mod,
-        mod_archive: ModArchive,
-        mod_dir_list: Vec<String>,
-        mod_file_list: Vec<String>,
+        installation: &GameInstallation,
+        mod_source: ModSource,
+        mapping: FileMapping,
     ) -> Result<()> {
         match self {
             InjectionType::Localization => {
-                let config = ModloaderConfig::load_config().await?;
+                let installation = installation.clone();
 
                 tauri::async_runtime::spawn_blocking(move || {
-                    // Create required directories if they do not yet exist
-                    for mod_dir in mod_dir_list.iter() {
-                        fs::create_dir_all(
-                            config
-                                .get_game_path()
-                                .ok_or(ModManagerError::AppNotInitialized)?
-                                .join("localization")
-                                .join(
-                                    config
-                                        .get_game_language()
-                                        .ok_or(ModManagerError::AppNotInitialized)?,
-                                )
-                                .join(mod_dir),
-                        )?;
+                    let languages = modification.target_languages(&installation);
+
+                    // Make sure all target languages exist before touching any game files
+                    for language in languages.iter() {
+                        if !installation.get_localization_path(language).is_dir() {
+                            return Err(ModManagerError::LanguageNotInstalled {
+                                language: language.to_owned(),
+                            });
+                        }
                     }
 
-                    let game_localization_path = config
-                        .get_game_path()
-                        .expect("Called activate_mod when game path has not been defined yet")
-                        .join("localization")
-                        .join(config.get_game_language().expect(
-                            "Called activate_mod when game language has not been defined yet",
-                        ));
+                    let mod_source = mod_source.reader()?;
+                    let path_prefix = modification.path_prefix().to_owned();
+                    let mod_dir_list = mapping.dirs();
+                    let mod_file_list = mapping.files();
 
-                    // Uncompress all required mod files and place them into the appropriate AW folder
-                    let mod_archive = File::open(mod_archive.path)?;
+                    let inject_language = |language: &str| -> Result<()> {
+                        let game_localization_path = installation.get_localization_path(language);
 
-                    for file in mod_file_list.iter() {
-                        let new_file = File::create(game_localization_path.join(file))?;
-                        compress_tools::uncompress_archive_file(&mod_archive, &new_file, file)?;
-                    }
+                        // Create required directories if they do not yet exist
+                        for mod_dir in mod_dir_list.iter() {
+                            fs::create_dir_all(game_localization_path.join(mod_dir))?;
+                        }
+
+                        // Extract all required mod files and place them into the appropriate AW folder
+                        for file in mod_file_list.iter() {
+                            let new_file = File::create(game_localization_path.join(file))?;
+                            mod_source.extract(
+                                &format!("{}{}", path_prefix, mapping.source_path(file)),
+                                &new_file,
+                            )?;
+                        }
+
+                        // Add newly added files to the file tree to detect future mod collisions
+                        FileTreeManager::insert_files(
+                            installation.get_name(),
+                            language,
+                            modification.uid,
+                            &mod_file_list,
+                        )
+                    };
+
+                    let mut injected_languages = vec![];
 
-                    // Add newly added files to the file tree to detect future mod collisions
-                    FileTreeManager::insert_files(modification.uid, &mod_file_list)?;
+                    for language in languages {
+                        injected_languages.push(language.clone());
+
+                        if let Err(err) = inject_language(&language) {
+                            remove_injected_files(
+                                &installation,
+                                &injected_languages,
+                                &mod_file_list,
+                            );
+                            return Err(err);
+                        }
+                    }
 
                     // Set added mod as active
-                    modification.set_active()
+                    if let Err(err) = modification.set_active(installation.get_name()) {
+                        remove_injected_files(&installation, &injected_languages, &mod_file_list);
+                        return Err(err);
+                    }
+
+                    Ok(())
+                })
+                .await?
+            }
+        }
+    }
+
+    /// Update the injected files of an active mod to a new version by only touching the files listed in the per language differences
+    ///
+    /// Returns the new version of the mod marked active in the installation. It is not stored in the DB, as the caller has to save the new version in the registry first. Conflicts and missing languages have to be checked prior to the update.
+    ///
+    /// If the update fails, all files of the mod are removed from the installation, as it would otherwise contain a mix of both versions. The caller has to inject the previous version again.
+    pub async fn update_mod(
+        &self,
+        mut modification: Mod,
+        installation: &GameInstallation,
+        mod_source: &ModSource,
+        changes: Vec<(String, FileDiff)>,
+        mapping: FileMapping,
+    ) -> Result<Mod> {
+        match self {
+            InjectionType::Localization => {
+                let installation = installation.clone();
+                let mod_source = mod_source.clone();
+
+                tauri::async_runtime::spawn_blocking(move || {
+                    let mod_source = mod_source.reader()?;
+                    let path_prefix = modification.path_prefix().to_owned();
+
+                    let update_language = |language: &str,
+                                           diff: &FileDiff,
+                                           written: &mut Vec<String>|
+                     -> Result<()> {
+                        let game_localization_path = installation.get_localization_path(language);
+
+                        for path in diff.removed.iter() {
+                            if let Err(err) = fs::remove_file(game_localization_path.join(path)) {
+                                match err.kind() {
+                                    std::io::ErrorKind::NotFound => (),
+                                    _ => return Err(err.into()),
+                                }
+                            }
+                        }
+
+                        FileTreeManager::remove_files(
+                            installation.get_name(),
+                            language,
+                            &diff.removed,
+                        )?;
+
+                        for file in diff.added.iter().chain(diff.changed.iter()) {
+                            let target_path = game_localization_path.join(file);
+
+                            if let Some(parent) = target_path.parent() {
+                                fs::create_dir_all(parent)?;
+                            }
+
+                            written.push(file.to_owned());
+
+                            let new_file = File::create(target_path)?;
+                            mod_source.extract(
+                                &format!("{}{}", path_prefix, mapping.source_path(file)),
+                                &new_file,
+                            )?;
+                        }
+
+                        FileTreeManager::insert_files(
+                            installation.get_name(),
+                            language,
+                            modification.uid,
+                            &diff.added,
+                        )
+                    };
+
+                    // Files written into each language, which are not part of the file tree yet if the update fails
+                    let mut written_files = vec![];
+
+                    for (language, diff) in changes.iter() {
+                        let mut written = vec![];
+                        let result = update_language(language, diff, &mut written);
+                        written_files.push((language.to_owned(), written));
+
+                        if let Err(err) = result {
+                            remove_updated_files(&installation, modification.uid, &written_files);
+                            return Err(err);
+                        }
+                    }
+
+                    modification.mark_active(installation.get_name());
+
+                    Ok(modification)
                 })
                 .await?
             }
         }
     }
 
-    /// Remove mod files from the game and deactivate it
-    pub async fn eject_mod(&self, modification: &Mod) -> Result<()> {
+    /// Remove mod files from every language of the provided game installation
+    ///
+    /// The files are looked up in the file tree of each language, so the mod is fully removed even if its target languages changed since the injection.
+    pub async fn eject_mod(
+        &self,
+        modification: &Mod,
+        installation: &GameInstallation,
+    ) -> Result<()> {
         match self {
             InjectionType::Localization => {
-                let config = ModloaderConfig::load_config().await?;
+                let installation = installation.clone();
+                let uid = modification.uid;
 
-                let modification = modification.clone();
                 tauri::async_runtime::spawn_blocking(move || {
-                    // get mod file paths from tree and remove them
-                    let file_paths = FileTreeManager::get_files(modification.uid)?;
-
-                    for path in file_paths.iter() {
-                        if let Err(err) = fs::remove_file(
-                            config
-                                .get_game_path()
-                                .ok_or(ModManagerError::AppNotInitialized)?
-                                .join("localization")
-                                .join(
-                                    config
-                                        .get_game_language()
-                                        .ok_or(ModManagerError::AppNotInitialized)?,
-                                )
-                                .join(path),
-                        ) {
-                            match err.kind() {
-                                std::io::ErrorKind::NotFound => (),
-                                _ => return Err(err.into()),
+                    for language in FileTreeManager::get_languages(installation.get_name()) {
+                        let game_localization_path = installation.get_localization_path(&language);
+
+                        // get mod file paths from tree and remove them
+                        let file_paths =
+                            FileTreeManager::get_files(installation.get_name(), &language, uid)?;
+
+                        for path in file_paths.iter() {
+                            if let Err(err) = fs::remove_file(game_localization_path.join(path)) {
+                                match err.kind() {
+                                    std::io::ErrorKind::NotFound => (),
+                                    _ => return Err(err.into()),
+                                }
                             }
                         }
+
+                        // update tree
+                        FileTreeManager::remove_files(
+                            installation.get_name(),
+                            &language,
+                            &file_paths,
+                        )?;
                     }
 
-                    // update tree
-                    FileTreeManager::remove_files(&file_paths)
+                    Ok(())
                 })
                 .await?
             }
         }
     }
 }
+
+/// Removes the files of a failed injection from the provided languages. Failures are only logged, as the error of the injection is reported instead.
+fn remove_injected_files(installation: &GameInstallation, languages: &[String], files: &[String]) {
+    for language in languages {
+        let game_localization_path = installation.get_localization_path(language);
+
+        for file in files {
+            if let Err(err) = fs::remove_file(game_localization_path.join(file)) {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!(
+                        "Failed to remove file {} of a failed injection: {:?}",
+                        file,
+                        err
+                    );
+                }
+            }
+        }
+
+        if let Err(err) =
+            FileTreeManager::remove_files(installation.get_name(), language, &files.to_vec())
+        {
+            log::warn!(
+                "Failed to remove files of a failed injection from the file tree: {:?}",
+                err
+            );
+        }
+    }
+}
+
+/// Removes all files of a mod from every language of the installation after updating them failed. The files written by the update are removed as well, even if they have not been added to the file tree yet.
+fn remove_updated_files(
+    installation: &GameInstallation,
+    uid: u64,
+    written_files: &[(String, Vec<String>)],
+) {
+    let mut languages = FileTreeManager::get_languages(installation.get_name());
+    languages.extend(
+        written_files
+            .iter()
+            .map(|(language, _)| language.to_owned()),
+    );
+    languages.sort();
+    languages.dedup();
+
+    for language in languages {
+        let mut files = FileTreeManager::get_files(installation.get_name(), &language, uid)
+            .unwrap_or_else(|err| {
+                log::warn!(
+                    "Failed to read the files of a failed update from the file tree: {:?}",
+                    err
+                );
+                vec![]
+            });
+
+        if let Some((_, written)) = written_files
+            .iter()
+            .find(|(written_language, _)| *written_language == language)
+        {
+            files.extend(written.iter().cloned());
+        }
+
+        files.sort();
+        files.dedup();
+
+        remove_injected_files(installation, &[language], &files);
+    }
+}
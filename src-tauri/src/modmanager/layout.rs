@@ -0,0 +1,171 @@
+//! Detection of common mistakes in the folder layout of mod archives
+//!
+//! Mod archives have to mirror the content of the `localization/<language>/` folder. Many archives wrap their files in an additional folder or contain the `localization/<language>/` folders themselves.
+//! Such layouts are detected and fixed by stripping a prefix from all archive paths on injection.
+//! Mods which place their files with the `files` field of the modinfo.json do not have to mirror the game, their prefix is the folder of the modinfo.json.
+use std::path::Path;
+
+use super::archive::{self, InvalidArchive};
+use super::error::{ModManagerError, Result};
+use super::preview;
+
+/// Top level folders inside the localization folder of a game language. An archive containing one of them at its base has a correct layout.
+///
+/// Only used if the localization folder of the game is not available, e.g. before an installation has been configured.
+const KNOWN_GAME_FOLDERS: [&str; 12] = [
+    "animations",
+    "fonts",
+    "levels",
+    "libs",
+    "materials",
+    "music",
+    "objects",
+    "particles",
+    "scripts",
+    "sounds",
+    "textures",
+    "weapons",
+];
+
+/// Extensions of documentation files which are ignored when analyzing the layout, e.g. a readme next to a wrapper folder
+const DOCUMENTATION_EXTENSIONS: [&str; 3] = [".txt", ".md", ".pdf"];
+
+/// Detects a misplaced archive layout and returns the prefix which has to be stripped from all archive paths, including the trailing slash
+///
+/// The folders of the archive are checked against the localization folder of the game, falling back to the known game folders if it is not available.
+/// Archives containing the localization folders of several game languages are rejected, as all files of a mod are injected into the same languages.
+pub fn detect_prefix(
+    entries: &[String],
+    localization_path: Option<&Path>,
+) -> Result<Option<String>> {
+    let files: Vec<&str> = entries
+        .iter()
+        .filter(|entry| !entry.ends_with('/') && !is_ignored(entry))
+        .map(String::as_str)
+        .collect();
+
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let mut prefix = String::new();
+
+    loop {
+        let remaining: Vec<&str> = files.iter().map(|file| &file[prefix.len()..]).collect();
+
+        let folder = match common_top_folder(&remaining) {
+            Some(folder) => folder,
+            // Wrapper folders are only stripped if all files inside of them are placed in game folders
+            None if !prefix.is_empty()
+                && !remaining
+                    .iter()
+                    .all(|path| starts_with_game_folder(path, localization_path)) =>
+            {
+                return Ok(keep_unknown_layout(&prefix));
+            }
+            None => break,
+        };
+
+        if folder.eq_ignore_ascii_case("localization") {
+            // The archive contains localization/<language>/ which is stripped entirely
+            let language_paths: Vec<&str> = remaining
+                .iter()
+                .map(|path| &path[folder.len() + 1..])
+                .collect();
+
+            match common_top_folder(&language_paths) {
+                Some(language) => prefix.push_str(&format!("{}/{}/", folder, language)),
+                None => {
+                    return Err(ModManagerError::InvalidArchive(
+                        InvalidArchive::MultipleLocalizations,
+                    ))
+                }
+            }
+
+            break;
+        }
+
+        if is_game_folder(folder, localization_path) {
+            break;
+        }
+
+        // An unknown folder containing all files is a wrapper folder, if the files inside of it are placed in game folders
+        prefix.push_str(folder);
+        prefix.push('/');
+    }
+
+    if prefix.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(prefix))
+    }
+}
+
+/// Keeps the layout of an archive whose files are wrapped in unknown folders without any known game folder inside, as it might be a correct mod for a game folder this modloader does not know
+fn keep_unknown_layout(prefix: &str) -> Option<String> {
+    log::warn!(
+        "The folder '{}' of the mod does not contain any known game folder, keeping the layout as it is",
+        prefix
+    );
+
+    None
+}
+
+/// The folder of the modinfo.json including the trailing slash, as the file rules of the modinfo.json are relative to it. None if the modinfo.json is at the base.
+pub fn modinfo_prefix(entries: &[String]) -> Option<String> {
+    archive::find_file(entries, "modinfo.json")
+        .and_then(|modinfo| modinfo.rsplit_once('/'))
+        .map(|(folder, _)| format!("{}/", folder))
+}
+
+/// The single top level folder which contains all provided paths. None if there are multiple folders or files at the base.
+fn common_top_folder<'a>(paths: &[&'a str]) -> Option<&'a str> {
+    let mut common_folder = None;
+
+    for path in paths {
+        let (folder, _) = path.split_once('/')?;
+
+        match common_folder {
+            None => common_folder = Some(folder),
+            Some(common_folder) if common_folder == folder => (),
+            Some(_) => return None,
+        }
+    }
+
+    common_folder
+}
+
+/// If the path is placed inside one of the top level folders of the localization folder
+fn starts_with_game_folder(path: &str, localization_path: Option<&Path>) -> bool {
+    path.split_once('/').map_or(false, |(folder, _)| {
+        is_game_folder(folder, localization_path)
+    })
+}
+
+/// If the folder exists in the localization folder of the game, or is a known game folder if the localization folder is not available
+fn is_game_folder(folder: &str, localization_path: Option<&Path>) -> bool {
+    match localization_path {
+        Some(localization_path) if localization_path.is_dir() => {
+            localization_path.join(folder).is_dir()
+        }
+        _ => is_known_game_folder(folder),
+    }
+}
+
+/// If the folder is one of the top level folders inside the localization folder of a game language
+pub fn is_known_game_folder(folder: &str) -> bool {
+    KNOWN_GAME_FOLDERS
+        .iter()
+        .any(|known_folder| known_folder.eq_ignore_ascii_case(folder))
+}
+
+/// Mod metadata, preview images and documentation files are not relevant for the layout
+fn is_ignored(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+
+    file_name == "modinfo.json"
+        || preview::PREVIEW_FILE_NAMES.contains(&file_name.as_str())
+        || DOCUMENTATION_EXTENSIONS
+            .iter()
+            .any(|extension| file_name.ends_with(extension))
+}
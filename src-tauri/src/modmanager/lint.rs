@@ -0,0 +1,1145 @@
+//! Linter of modinfo.json files which reports all problems of a file at once
+//!
+//! Every problem is reported with its position in the file, the affected field and a suggestion how to fix it, so mod authors do not have to decipher serde errors.
+use std::fmt;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config;
+
+use super::archive::MODINFO_SCHEMA_VERSION;
+use super::mapping::is_relative_path;
+
+/// Fields of the modinfo.json file, whether they are required and an example value used in suggestions
+const FIELDS: [(&str, bool, &str); 18] = [
+    ("schema_version", false, "2"),
+    ("id", false, "\"author.modname\""),
+    ("name", true, "\"My mod name\""),
+    ("author", true, "\"Your name\""),
+    ("version", true, "\"1.0.0\""),
+    ("info", true, "\"Description of the mod\""),
+    ("injection", true, "\"localization\""),
+    ("languages", false, "[\"en\"]"),
+    ("changelog", false, "{ \"1.0.0\": \"Initial release\" }"),
+    ("description", false, "\"Short summary of the mod\""),
+    ("homepage", false, "\"https://example.com\""),
+    ("license", false, "\"CC-BY-4.0\""),
+    ("tags", false, "[\"units\"]"),
+    ("min_modloader_version", false, "\"0.1.0\""),
+    ("supported_languages", false, "[\"en\"]"),
+    ("preview", false, "\"preview.png\""),
+    ("options", false, OPTIONS_EXAMPLE),
+    ("files", false, FILES_EXAMPLE),
+];
+
+/// Example of an option group used in suggestions
+const OPTIONS_EXAMPLE: &str =
+    "[{ \"name\": \"Color\", \"choices\": [{ \"name\": \"Red\", \"folder\": \"options/red\" }] }]";
+
+/// Example of a file rule used in suggestions
+const FILES_EXAMPLE: &str = "[{ \"source\": \"camos/*.dds\", \"target\": \"textures/camo\" }]";
+
+/// Fields which have been added in schema version 2
+const EXTENDED_FIELDS: [&str; 9] = [
+    "description",
+    "homepage",
+    "license",
+    "tags",
+    "min_modloader_version",
+    "supported_languages",
+    "preview",
+    "options",
+    "files",
+];
+
+/// Fields which can contain translations keyed by language code since schema version 2
+const TRANSLATABLE_FIELDS: [&str; 2] = ["name", "info"];
+
+/// Extensions of the image formats supported as preview
+const PREVIEW_EXTENSIONS: [&str; 3] = [".png", ".jpg", ".jpeg"];
+
+/// Names of the injection types accepted by the modloader, which are case sensitive
+const INJECTION_TYPES: [&str; 2] = ["localization", "Localization"];
+
+/// Language codes supported in the languages field
+const LANGUAGE_CODES: [&str; 5] = ["en", "de", "fr", "pl", "ru"];
+
+/// Unknown fields which differ from a known field by at most this number of characters are reported as typo
+const MAX_TYPO_DISTANCE: usize = 2;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The file is rejected by the modloader
+    Error,
+    /// The file is accepted, but most likely does not do what the author intended
+    Warning,
+}
+
+/// Position in the file, both starting at 1
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A single problem of a modinfo.json file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    /// The affected field, None if the problem concerns the whole file
+    pub field: Option<String>,
+    pub message: String,
+    /// How to fix the problem
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    fn new(
+        severity: Severity,
+        position: Position,
+        field: Option<&str>,
+        message: String,
+        suggestion: Option<String>,
+    ) -> Self {
+        Self {
+            severity,
+            line: position.line,
+            column: position.column,
+            field: field.map(str::to_owned),
+            message,
+            suggestion,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}, column {}", self.line, self.column)?;
+
+        if let Some(field) = &self.field {
+            write!(f, " ({})", field)?;
+        }
+
+        write!(f, ": {}", self.message)?;
+
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " {}", suggestion)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks the content of a modinfo.json file and returns all problems in the order of their position
+pub fn lint(source: &str) -> Vec<Diagnostic> {
+    let value: Value = match serde_json::from_str(source) {
+        Ok(value) => value,
+        Err(err) => {
+            let message = err.to_string();
+            let message = message.split(" at line ").next().unwrap_or(&message);
+
+            return vec![Diagnostic::new(
+                Severity::Error,
+                Position {
+                    line: err.line(),
+                    column: err.column(),
+                },
+                None,
+                format!("The file is not valid JSON: {}", message),
+                Some(String::from(
+                    "Check for missing commas, quotes or brackets around this position.",
+                )),
+            )];
+        }
+    };
+
+    let root = positions(source)
+        .find(|(_, character)| !character.is_whitespace())
+        .map(|(position, _)| position)
+        .unwrap_or(Position { line: 1, column: 1 });
+
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => {
+            return vec![Diagnostic::new(
+                Severity::Error,
+                root,
+                None,
+                String::from("The file has to contain a JSON object"),
+                Some(String::from(
+                    "Wrap the fields in curly braces: { \"name\": ... }",
+                )),
+            )]
+        }
+    };
+
+    let mut diagnostics = vec![];
+
+    // Files without schema version follow schema version 1
+    let schema_version = object
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(1);
+
+    for key in top_level_keys(source) {
+        let value = match object.get(&key.name) {
+            Some(value) => value,
+            None => continue,
+        };
+
+        if FIELDS.iter().any(|(field, _, _)| *field == key.name) {
+            check_field(&key.name, value, key.value, &mut diagnostics);
+
+            // The field is still read, but older modloaders ignore or reject it
+            if schema_version < 2 {
+                let message = if EXTENDED_FIELDS.contains(&key.name.as_str()) {
+                    Some(format!(
+                        "The field '{}' requires schema version 2",
+                        key.name
+                    ))
+                } else if TRANSLATABLE_FIELDS.contains(&key.name.as_str()) && value.is_object() {
+                    Some(format!(
+                        "Translations of the field '{}' require schema version 2",
+                        key.name
+                    ))
+                } else {
+                    None
+                };
+
+                if let Some(message) = message {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Warning,
+                        key.key,
+                        Some(&key.name),
+                        message,
+                        Some(String::from("Add \"schema_version\": 2 to the object.")),
+                    ));
+                }
+            }
+        } else if let Some(field) = case_insensitive_field(&key.name) {
+            // The field is ignored like any unknown field, a missing required field is reported separately
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                key.key,
+                Some(&key.name),
+                format!(
+                    "Unknown field '{}', field names are case sensitive",
+                    key.name
+                ),
+                Some(format!("Did you mean '{}'?", field)),
+            ));
+        } else {
+            let suggestion = match closest_field(&key.name) {
+                Some(field) => format!("Did you mean '{}'?", field),
+                None => String::from("Remove the field, it is ignored by the modloader."),
+            };
+
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                key.key,
+                Some(&key.name),
+                format!("Unknown field '{}'", key.name),
+                Some(suggestion),
+            ));
+        }
+    }
+
+    for (field, required, example) in FIELDS {
+        if !required || object.contains_key(field) {
+            continue;
+        }
+
+        let suggestion = match object.keys().find(|key| key.eq_ignore_ascii_case(field)) {
+            Some(key) => format!(
+                "Did you mean '{}'? Rename it to '{}', field names are case sensitive.",
+                key, field
+            ),
+            None => format!("Add \"{}\": {} to the object.", field, example),
+        };
+
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            root,
+            Some(field),
+            format!("The required field '{}' is missing", field),
+            Some(suggestion),
+        ));
+    }
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+
+    diagnostics
+}
+
+/// Checks the value of a known field
+fn check_field(field: &str, value: &Value, position: Position, diagnostics: &mut Vec<Diagnostic>) {
+    // Optional fields can be set to null
+    if value.is_null()
+        && FIELDS
+            .iter()
+            .any(|(name, required, _)| *name == field && !required)
+    {
+        return;
+    }
+
+    let mut report = |severity, message: String, suggestion: Option<String>| {
+        diagnostics.push(Diagnostic::new(
+            severity,
+            position,
+            Some(field),
+            message,
+            suggestion,
+        ))
+    };
+
+    match field {
+        "schema_version" => match value.as_u64() {
+            Some(version) if version > u64::from(MODINFO_SCHEMA_VERSION) => report(
+                Severity::Error,
+                format!(
+                    "Schema version {} is not supported by this modloader version",
+                    version
+                ),
+                Some(format!(
+                    "Update the modloader or use schema version {}.",
+                    MODINFO_SCHEMA_VERSION
+                )),
+            ),
+            Some(version) if version > 0 => (),
+            _ => report(
+                Severity::Error,
+                format!("'{}' is not a valid schema version", unquoted(value)),
+                Some(format!(
+                    "Use \"schema_version\": {}.",
+                    MODINFO_SCHEMA_VERSION
+                )),
+            ),
+        },
+        "languages" | "supported_languages" => {
+            let languages = match value.as_array() {
+                Some(languages) => languages,
+                None => {
+                    return report(
+                        Severity::Error,
+                        format!("The field '{}' has to be a list of language codes", field),
+                        Some(String::from("Use a list like [\"en\", \"de\"].")),
+                    )
+                }
+            };
+
+            for language in languages {
+                let language = match language.as_str() {
+                    Some(language) => language,
+                    None => {
+                        report(
+                            Severity::Error,
+                            format!("The language {} is not a string", language),
+                            Some(String::from("Put the language code in quotes.")),
+                        );
+                        continue;
+                    }
+                };
+
+                if config::game_language_from_code(language).is_err() {
+                    report(
+                        Severity::Error,
+                        format!("Unsupported game language '{}'", language),
+                        Some(language_suggestion(language)),
+                    );
+                }
+            }
+        }
+        "changelog" => {
+            let changelog = match value.as_object() {
+                Some(changelog) => changelog,
+                None => {
+                    return report(
+                        Severity::Error,
+                        String::from("The changelog has to map versions to their notes"),
+                        Some(String::from(
+                            "Use an object like { \"1.0.0\": \"Initial release\" }.",
+                        )),
+                    )
+                }
+            };
+
+            check_changelog(changelog, &mut report);
+        }
+        "name" | "info" if value.is_object() => check_translations(field, value, &mut report),
+        "options" => check_options(value, &mut report),
+        "files" => check_files(value, &mut report),
+        "tags" => {
+            let tags = match value.as_array() {
+                Some(tags) => tags,
+                None => {
+                    return report(
+                        Severity::Error,
+                        String::from("The tags have to be a list of strings"),
+                        Some(String::from("Use a list like [\"units\", \"balance\"].")),
+                    )
+                }
+            };
+
+            for tag in tags {
+                match tag.as_str() {
+                    Some(tag) if tag.trim().is_empty() => report(
+                        Severity::Warning,
+                        String::from("Empty tags are ignored"),
+                        Some(String::from("Remove the empty tag.")),
+                    ),
+                    Some(_) => (),
+                    None => report(
+                        Severity::Error,
+                        format!("The tag {} is not a string", tag),
+                        Some(format!("Put the tag in quotes: \"{}\".", unquoted(tag))),
+                    ),
+                }
+            }
+        }
+        _ => {
+            let text = match value.as_str() {
+                Some(text) => text,
+                None => {
+                    return report(
+                        Severity::Error,
+                        format!("The field '{}' has to be a string", field),
+                        Some(format!("Put the value in quotes: \"{}\".", unquoted(value))),
+                    )
+                }
+            };
+
+            match field {
+                "id" | "name" if text.trim().is_empty() => report(
+                    Severity::Error,
+                    format!("The mod {} must not be empty", field),
+                    None,
+                ),
+                "version" | "min_modloader_version" => {
+                    if let Err(err) = Version::parse(text) {
+                        report(
+                            Severity::Error,
+                            format!("'{}' is not a valid semver version: {}", text, err),
+                            Some(version_suggestion(text)),
+                        );
+                    }
+                }
+                // Only shown as link, the mod works without it
+                "homepage" if !text.starts_with("https://") && !text.starts_with("http://") => {
+                    report(
+                        Severity::Warning,
+                        format!("'{}' is not a valid homepage URL", text),
+                        Some(String::from(
+                            "Use a full URL starting with https://, e.g. \"https://example.com\".",
+                        )),
+                    )
+                }
+                "preview" => {
+                    if !is_relative_path(text) {
+                        report(
+                            Severity::Error,
+                            format!("The preview '{}' is not a path inside the mod", text),
+                            Some(String::from(
+                                "Use a path relative to the modinfo.json, e.g. \"preview.png\".",
+                            )),
+                        );
+                    } else if !PREVIEW_EXTENSIONS
+                        .iter()
+                        .any(|extension| text.to_lowercase().ends_with(extension))
+                    {
+                        // The mod is added without thumbnail if the image can not be read
+                        report(
+                            Severity::Warning,
+                            format!("The preview '{}' is not a PNG or JPEG image", text),
+                            Some(String::from("Convert the image to PNG or JPEG.")),
+                        );
+                    }
+                }
+                "injection" if !INJECTION_TYPES.contains(&text) => {
+                    report(
+                        Severity::Error,
+                        format!("Unknown injection type '{}'", text),
+                        Some(if text.eq_ignore_ascii_case("localization") {
+                            String::from("Injection types are case sensitive, did you mean \"localization\"?")
+                        } else {
+                            String::from(
+                                "Use \"localization\", it is the only supported injection type.",
+                            )
+                        }),
+                    )
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+fn check_translations(
+    field: &str,
+    value: &Value,
+    report: &mut impl FnMut(Severity, String, Option<String>),
+) {
+    let translations = match value.as_object() {
+        Some(translations) => translations,
+        None => return,
+    };
+
+    if translations.is_empty() {
+        return report(
+            Severity::Error,
+            format!("The field '{}' does not contain any translation", field),
+            Some(String::from(
+                "Add a translation like { \"en\": \"...\" } or use a single text.",
+            )),
+        );
+    }
+
+    for (language, text) in translations {
+        if !LANGUAGE_CODES.contains(&language.as_str()) {
+            report(
+                Severity::Warning,
+                format!(
+                    "The translation '{}' is never shown, as it is no supported language code",
+                    language
+                ),
+                Some(language_suggestion(language)),
+            );
+        }
+
+        match text.as_str() {
+            Some(text) if field == "name" && text.trim().is_empty() => report(
+                Severity::Error,
+                format!("The mod name in language '{}' must not be empty", language),
+                None,
+            ),
+            Some(_) => (),
+            None => report(
+                Severity::Error,
+                format!("The translation '{}' has to be a string", language),
+                Some(format!("Put the value in quotes: \"{}\".", unquoted(text))),
+            ),
+        }
+    }
+}
+
+fn check_options(value: &Value, report: &mut impl FnMut(Severity, String, Option<String>)) {
+    let groups = match value.as_array() {
+        Some(groups) => groups,
+        None => {
+            return report(
+                Severity::Error,
+                String::from("The options have to be a list of option groups"),
+                Some(format!("Use a list like {}.", OPTIONS_EXAMPLE)),
+            )
+        }
+    };
+
+    let mut group_names = vec![];
+
+    for group in groups {
+        let name = match group.get("name").and_then(Value::as_str) {
+            Some(name) if !name.trim().is_empty() => name,
+            _ => {
+                report(
+                    Severity::Error,
+                    String::from("Every option group needs a name"),
+                    Some(String::from("Add \"name\": \"Color\" to the option group.")),
+                );
+                continue;
+            }
+        };
+
+        if group_names.contains(&name) {
+            report(
+                Severity::Error,
+                format!("The option group '{}' exists multiple times", name),
+                Some(String::from("Give every option group a unique name.")),
+            );
+        }
+
+        group_names.push(name);
+
+        let choices = match group.get("choices").and_then(Value::as_array) {
+            Some(choices) if !choices.is_empty() => choices,
+            _ => {
+                report(
+                    Severity::Error,
+                    format!("The option group '{}' needs at least one choice", name),
+                    Some(String::from("Add a list of choices to the option group.")),
+                );
+                continue;
+            }
+        };
+
+        let mut choice_names = vec![];
+
+        for choice in choices {
+            let choice_name = match choice.get("name").and_then(Value::as_str) {
+                Some(choice_name) if !choice_name.trim().is_empty() => choice_name,
+                _ => {
+                    report(
+                        Severity::Error,
+                        format!("Every choice of the option group '{}' needs a name", name),
+                        Some(String::from("Add \"name\": \"Red\" to the choice.")),
+                    );
+                    continue;
+                }
+            };
+
+            if choice_names.contains(&choice_name) {
+                report(
+                    Severity::Error,
+                    format!(
+                        "The choice '{}' exists multiple times in the option group '{}'",
+                        choice_name, name
+                    ),
+                    Some(String::from("Give every choice of a group a unique name.")),
+                );
+            }
+
+            choice_names.push(choice_name);
+
+            let has_folder = choice
+                .get("folder")
+                .and_then(Value::as_str)
+                .filter(|folder| !folder.trim_matches(['/', '\\']).is_empty())
+                .map_or(false, is_relative_path);
+
+            if !has_folder {
+                report(
+                    Severity::Error,
+                    format!(
+                        "The choice '{}' of the option group '{}' needs a folder inside the mod",
+                        choice_name, name
+                    ),
+                    Some(String::from(
+                        "Use a folder relative to the modinfo.json, e.g. \"folder\": \"options/red\".",
+                    )),
+                );
+            }
+        }
+    }
+}
+
+fn check_files(value: &Value, report: &mut impl FnMut(Severity, String, Option<String>)) {
+    let rules = match value.as_array() {
+        Some(rules) => rules,
+        None => {
+            return report(
+                Severity::Error,
+                String::from("The files have to be a list of file rules"),
+                Some(format!("Use a list like {}.", FILES_EXAMPLE)),
+            )
+        }
+    };
+
+    for rule in rules {
+        let source = match rule.get("source").and_then(Value::as_str) {
+            Some(source) if !source.trim().is_empty() => source,
+            _ => {
+                report(
+                    Severity::Error,
+                    String::from("Every file rule needs a source pattern"),
+                    Some(String::from(
+                        "Add \"source\": \"camos/*.dds\" to the file rule.",
+                    )),
+                );
+                continue;
+            }
+        };
+
+        if !is_relative_path(source) {
+            report(
+                Severity::Error,
+                format!("The file pattern '{}' is not a path inside the mod", source),
+                Some(String::from(
+                    "Use a pattern relative to the modinfo.json, e.g. \"camos/*.dds\".",
+                )),
+            );
+        }
+
+        match rule.get("target").and_then(Value::as_str) {
+            Some(target) if !is_relative_path(target) => report(
+                Severity::Error,
+                format!("The target folder '{}' is outside of the game", target),
+                Some(String::from(
+                    "Use a folder relative to the localization folder, e.g. \"textures/camo\".",
+                )),
+            ),
+            Some(_) => (),
+            None => report(
+                Severity::Error,
+                format!("The file rule '{}' needs a target folder", source),
+                Some(String::from(
+                    "Add \"target\": \"textures/camo\" to the file rule, use \"\" for the base of the game.",
+                )),
+            ),
+        }
+    }
+}
+
+fn check_changelog(
+    changelog: &Map<String, Value>,
+    report: &mut impl FnMut(Severity, String, Option<String>),
+) {
+    for (version, notes) in changelog {
+        if let Err(err) = Version::parse(version) {
+            report(
+                Severity::Error,
+                format!("Invalid changelog version '{}': {}", version, err),
+                Some(version_suggestion(version)),
+            );
+        }
+
+        if !notes.is_string() {
+            report(
+                Severity::Error,
+                format!("The notes of version '{}' have to be a string", version),
+                Some(String::from("Use \\n for line breaks inside of the notes.")),
+            );
+        }
+    }
+}
+
+/// Suggests how to turn an invalid version into a semver version
+fn version_suggestion(version: &str) -> String {
+    let version = version.trim();
+
+    if let Some(stripped) = version.strip_prefix(['v', 'V']) {
+        if Version::parse(stripped).is_ok() {
+            return format!("Remove the leading 'v': \"{}\".", stripped);
+        }
+    }
+
+    let numbers: Vec<&str> = version.split('.').collect();
+
+    if numbers.len() < 3
+        && numbers
+            .iter()
+            .all(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+    {
+        let mut numbers = numbers;
+        numbers.resize(3, "0");
+
+        return format!("Versions need three numbers: \"{}\".", numbers.join("."));
+    }
+
+    String::from("Use a version like \"1.0.0\", see https://semver.org.")
+}
+
+/// Suggests the language code of a game language, e.g. for "English"
+fn language_suggestion(language: &str) -> String {
+    let mut characters = language.chars();
+    let capitalized: String = match characters.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(characters.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    };
+
+    match config::game_language_to_code(&capitalized) {
+        Ok(code) => format!("Use the language code \"{}\".", code),
+        Err(_) if LANGUAGE_CODES.contains(&language.to_lowercase().as_str()) => {
+            format!(
+                "Language codes are lowercase: \"{}\".",
+                language.to_lowercase()
+            )
+        }
+        Err(_) => format!(
+            "Supported language codes are {}.",
+            LANGUAGE_CODES.join(", ")
+        ),
+    }
+}
+
+/// The known field which only differs from the unknown field in its case
+fn case_insensitive_field(name: &str) -> Option<&'static str> {
+    FIELDS
+        .iter()
+        .map(|(field, _, _)| *field)
+        .find(|field| field.eq_ignore_ascii_case(name))
+}
+
+/// The known field which is closest to the unknown field, if it is likely a typo
+fn closest_field(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+
+    FIELDS
+        .iter()
+        .map(|(field, _, _)| (*field, edit_distance(&name, field)))
+        .filter(|(_, distance)| *distance <= MAX_TYPO_DISTANCE)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(field, _)| field)
+}
+
+/// Levenshtein distance between the two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// The value as it would be written inside of quotes
+fn unquoted(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// A key of the top level object with the positions of the key and its value
+struct KeyPosition {
+    name: String,
+    key: Position,
+    value: Position,
+}
+
+/// Finds the keys of the top level object in a syntactically valid JSON document
+fn top_level_keys(source: &str) -> Vec<KeyPosition> {
+    let mut keys = vec![];
+    let mut characters = positions(source);
+    let mut depth = 0;
+    let mut expect_key = false;
+    // Key whose value has not been reached yet
+    let mut pending_key: Option<(String, Position)> = None;
+
+    while let Some((position, character)) = characters.next() {
+        if depth == 1 && !character.is_whitespace() && character != ':' && character != ',' {
+            if let Some((name, key)) = pending_key.take() {
+                keys.push(KeyPosition {
+                    name,
+                    key,
+                    value: position,
+                });
+
+                // The value is skipped below, strings are consumed as a whole
+                if character == '"' {
+                    read_string(&mut characters);
+                    continue;
+                }
+            }
+        }
+
+        match character {
+            '"' => {
+                let string = read_string(&mut characters);
+
+                if depth == 1 && expect_key {
+                    pending_key = Some((string, position));
+                    expect_key = false;
+                }
+            }
+            '{' | '[' => {
+                depth += 1;
+                expect_key = depth == 1 && character == '{';
+            }
+            '}' | ']' => depth -= 1,
+            ',' if depth == 1 => expect_key = true,
+            _ => (),
+        }
+    }
+
+    keys
+}
+
+/// Reads the rest of a string after its opening quote and decodes its escape sequences
+fn read_string(characters: &mut impl Iterator<Item = (Position, char)>) -> String {
+    let mut raw = String::new();
+
+    while let Some((_, character)) = characters.next() {
+        match character {
+            '"' => break,
+            '\\' => {
+                raw.push(character);
+
+                if let Some((_, escaped)) = characters.next() {
+                    raw.push(escaped);
+                }
+            }
+            character => raw.push(character),
+        }
+    }
+
+    // The document is valid JSON, so the string is decoded exactly like the parsed value. The raw key is kept otherwise.
+    serde_json::from_str(&format!("\"{}\"", raw)).unwrap_or(raw)
+}
+
+/// The characters of the source together with their position
+fn positions(source: &str) -> impl Iterator<Item = (Position, char)> + '_ {
+    let mut position = Position { line: 1, column: 0 };
+
+    source.chars().map(move |character| {
+        position.column += 1;
+        let current = position;
+
+        if character == '\n' {
+            position.line += 1;
+            position.column = 0;
+        }
+
+        (current, character)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A modinfo.json with one field per line, the first field is on line 2 and every key starts at column 3
+    fn modinfo(fields: &[&str]) -> String {
+        let fields: Vec<String> = fields.iter().map(|field| format!("  {}", field)).collect();
+
+        format!("{{\n{}\n}}", fields.join(",\n"))
+    }
+
+    fn assert_diagnostic(
+        diagnostic: &Diagnostic,
+        severity: Severity,
+        (line, column): (usize, usize),
+        field: &str,
+        suggestion: &str,
+    ) {
+        assert_eq!(diagnostic.severity, severity, "{}", diagnostic);
+        assert_eq!(
+            (diagnostic.line, diagnostic.column),
+            (line, column),
+            "{}",
+            diagnostic
+        );
+        assert_eq!(diagnostic.field.as_deref(), Some(field), "{}", diagnostic);
+        assert_eq!(
+            diagnostic.suggestion.as_deref(),
+            Some(suggestion),
+            "{}",
+            diagnostic
+        );
+    }
+
+    #[test]
+    fn valid_modinfo() {
+        let source = modinfo(&[
+            r#""name": "Tank""#,
+            r#""author": "Me""#,
+            r#""version": "1.0.0""#,
+            r#""info": "Info""#,
+            r#""injection": "localization""#,
+        ]);
+
+        assert!(lint(&source).is_empty());
+    }
+
+    #[test]
+    fn missing_fields() {
+        let source = modinfo(&[
+            r#""name": "Tank""#,
+            r#""version": "1.0.0""#,
+            r#""info": "Info""#,
+        ]);
+        let diagnostics = lint(&source);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_diagnostic(
+            &diagnostics[0],
+            Severity::Error,
+            (1, 1),
+            "author",
+            "Add \"author\": \"Your name\" to the object.",
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "The required field 'author' is missing"
+        );
+        assert_diagnostic(
+            &diagnostics[1],
+            Severity::Error,
+            (1, 1),
+            "injection",
+            "Add \"injection\": \"localization\" to the object.",
+        );
+    }
+
+    #[test]
+    fn bad_semver() {
+        let source = modinfo(&[
+            r#""name": "Tank""#,
+            r#""author": "Me""#,
+            r#""version": "v1.0.0""#,
+            r#""info": "Info""#,
+            r#""injection": "localization""#,
+            r#""schema_version": 2"#,
+            r#""min_modloader_version": "1.0""#,
+        ]);
+        let diagnostics = lint(&source);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_diagnostic(
+            &diagnostics[0],
+            Severity::Error,
+            (4, 14),
+            "version",
+            "Remove the leading 'v': \"1.0.0\".",
+        );
+        assert!(diagnostics[0]
+            .message
+            .starts_with("'v1.0.0' is not a valid semver version"));
+        assert_diagnostic(
+            &diagnostics[1],
+            Severity::Error,
+            (8, 28),
+            "min_modloader_version",
+            "Versions need three numbers: \"1.0.0\".",
+        );
+    }
+
+    #[test]
+    fn unknown_injection_type() {
+        let source = modinfo(&[
+            r#""name": "Tank""#,
+            r#""author": "Me""#,
+            r#""version": "1.0.0""#,
+            r#""info": "Info""#,
+            r#""injection": "textures""#,
+        ]);
+        let diagnostics = lint(&source);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_diagnostic(
+            &diagnostics[0],
+            Severity::Error,
+            (6, 16),
+            "injection",
+            "Use \"localization\", it is the only supported injection type.",
+        );
+        assert_eq!(diagnostics[0].message, "Unknown injection type 'textures'");
+
+        let diagnostics = lint(&source.replace("textures", "LOCALIZATION"));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_diagnostic(
+            &diagnostics[0],
+            Severity::Error,
+            (6, 16),
+            "injection",
+            "Injection types are case sensitive, did you mean \"localization\"?",
+        );
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let source = modinfo(&[
+            r#""name": "Tank""#,
+            r#""author": "Me""#,
+            r#""version": "1.0.0""#,
+            r#""info": "Info""#,
+            r#""injection": "localization""#,
+            r#""licence": "MIT""#,
+            r#""color": "red""#,
+        ]);
+        let diagnostics = lint(&source);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_diagnostic(
+            &diagnostics[0],
+            Severity::Warning,
+            (7, 3),
+            "licence",
+            "Did you mean 'license'?",
+        );
+        assert_eq!(diagnostics[0].message, "Unknown field 'licence'");
+        assert_diagnostic(
+            &diagnostics[1],
+            Severity::Warning,
+            (8, 3),
+            "color",
+            "Remove the field, it is ignored by the modloader.",
+        );
+    }
+
+    #[test]
+    fn homepage_and_preview_do_not_reject_the_file() {
+        let source = modinfo(&[
+            r#""schema_version": 2"#,
+            r#""name": "Tank""#,
+            r#""author": "Me""#,
+            r#""version": "1.0.0""#,
+            r#""info": "Info""#,
+            r#""injection": "localization""#,
+            r#""homepage": "example.com""#,
+            r#""preview": "preview.bmp""#,
+        ]);
+        let diagnostics = lint(&source);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_diagnostic(
+            &diagnostics[0],
+            Severity::Warning,
+            (8, 15),
+            "homepage",
+            "Use a full URL starting with https://, e.g. \"https://example.com\".",
+        );
+        assert_diagnostic(
+            &diagnostics[1],
+            Severity::Warning,
+            (9, 14),
+            "preview",
+            "Convert the image to PNG or JPEG.",
+        );
+
+        // A preview outside of the mod still rejects the file
+        let diagnostics = lint(&source.replace("preview.bmp", "../preview.png"));
+
+        assert!(diagnostics[1].is_error());
+    }
+
+    #[test]
+    fn wrong_casing() {
+        let source = modinfo(&[
+            r#""name": "Tank""#,
+            r#""Author": "Me""#,
+            r#""version": "1.0.0""#,
+            r#""info": "Info""#,
+            r#""injection": "localization""#,
+        ]);
+        let diagnostics = lint(&source);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_diagnostic(
+            &diagnostics[0],
+            Severity::Error,
+            (1, 1),
+            "author",
+            "Did you mean 'Author'? Rename it to 'author', field names are case sensitive.",
+        );
+        assert_diagnostic(
+            &diagnostics[1],
+            Severity::Warning,
+            (3, 3),
+            "Author",
+            "Did you mean 'author'?",
+        );
+        assert_eq!(
+            diagnostics[1].message,
+            "Unknown field 'Author', field names are case sensitive"
+        );
+    }
+}
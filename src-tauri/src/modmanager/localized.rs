@@ -0,0 +1,76 @@
+//! Texts of the modinfo.json which can be translated into several languages
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Language code of the translation which is used if the requested language is not available
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+/// A text of the modinfo.json, either a single text for all languages or translations keyed by language code
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum LocalizedText {
+    Text(String),
+    Translations(BTreeMap<String, String>),
+}
+
+impl LocalizedText {
+    /// The text in the provided language. Falls back to English or the first available translation if the language is not available.
+    pub fn get(&self, language: Option<&str>) -> &str {
+        match self {
+            Self::Text(text) => text,
+            Self::Translations(translations) => {
+                select(translations, language).map_or("", |(_, text)| text.as_str())
+            }
+        }
+    }
+
+    /// Replaces the text which is shown if the app language is not available, which is the English or first translation. Other translations are kept.
+    pub fn set_default(&mut self, text: String) {
+        match self {
+            Self::Translations(translations) if !translations.is_empty() => {
+                let language = select(translations, None)
+                    .map(|(language, _)| language.clone())
+                    .unwrap_or_else(|| FALLBACK_LANGUAGE.to_owned());
+
+                translations.insert(language, text);
+            }
+            _ => *self = Self::Text(text),
+        }
+    }
+
+    /// All translations keyed by language code, empty if the text is not translated
+    pub fn translations(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::Text(_) => BTreeMap::new(),
+            Self::Translations(translations) => translations.clone(),
+        }
+    }
+
+    /// Whether there is no text or any of the translations is blank
+    pub fn is_blank(&self) -> bool {
+        match self {
+            Self::Text(text) => text.trim().is_empty(),
+            Self::Translations(translations) => {
+                translations.is_empty() || translations.values().any(|text| text.trim().is_empty())
+            }
+        }
+    }
+}
+
+impl From<String> for LocalizedText {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+/// Selects the translation in the provided language, otherwise the English or the first translation
+pub fn select<'a>(
+    translations: &'a BTreeMap<String, String>,
+    language: Option<&str>,
+) -> Option<(&'a String, &'a String)> {
+    language
+        .and_then(|language| translations.get_key_value(language))
+        .or_else(|| translations.get_key_value(FALLBACK_LANGUAGE))
+        .or_else(|| translations.iter().next())
+}
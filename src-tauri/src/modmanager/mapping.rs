@@ -0,0 +1,201 @@
+//! Mapping of the files of a mod source to their paths inside the game
+//!
+//! Conflict detection, hashing and injection work with the paths inside the game, while the files are extracted from their path inside the mod source.
+//! Mod authors can remap the files with the `files` field of the modinfo.json, so the archive does not have to follow the folder layout of the game.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{ModManagerError, Result};
+use super::glob::Pattern;
+use super::options::OptionGroup;
+
+/// A rule of the `files` field of the modinfo.json, placing the files matching a glob pattern into a folder of the game
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FileRule {
+    /// Glob pattern of the files inside the mod, relative to the modinfo.json
+    pub source: String,
+    /// Folder inside the game the matching files are placed in. The path of a file below the literal base of the pattern is kept.
+    pub target: String,
+}
+
+impl FileRule {
+    /// Checks that the pattern points inside the mod and the target folder inside the game
+    pub fn validate(&self) -> Result<()> {
+        if self.source.trim().is_empty() || !is_relative_path(&self.source) {
+            return Err(ModManagerError::InvalidModInfo {
+                msg: format!(
+                    "The file pattern '{}' is not a path inside the mod",
+                    self.source
+                ),
+            });
+        }
+
+        if !is_relative_path(&self.target) {
+            return Err(ModManagerError::InvalidModInfo {
+                msg: format!(
+                    "The target folder '{}' is not a folder inside the game",
+                    self.target
+                ),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The target folder relative to the game root, including the trailing slash. Empty for the game root.
+    fn target_prefix(&self) -> String {
+        self.target
+            .replace('\\', "/")
+            .split('/')
+            .filter(|component| !component.is_empty() && *component != ".")
+            .map(|component| format!("{}/", component))
+            .collect()
+    }
+}
+
+/// The files of a mod inside the game together with their path inside the mod source
+#[derive(Debug, Clone, Default)]
+pub struct FileMapping {
+    /// Paths inside the game mapped to the paths inside the mod source, both relative to the path prefix
+    files: BTreeMap<String, String>,
+}
+
+impl FileMapping {
+    /// Maps the files of a mod source, relative to the path prefix, to the game
+    ///
+    /// Files inside the folder of an option choice are only injected if the choice is selected and replace the files in the base of the mod. The preview image is not injected.
+    ///
+    /// Afterwards the file rules place every file matching a rule into the target folder of the rule, a file matching several rules is placed into several folders. Files which do not match any rule keep their path. Files which would end up outside of the game root are skipped.
+    pub fn new(
+        source_files: &[String],
+        preview: Option<&str>,
+        option_groups: &[OptionGroup],
+        selected_options: &BTreeMap<String, String>,
+        file_rules: &[FileRule],
+    ) -> Self {
+        let option_folders: Vec<String> = option_groups
+            .iter()
+            .flat_map(|group| group.choices.iter())
+            .map(|choice| choice.folder_prefix())
+            .collect();
+
+        let mut files: BTreeMap<String, String> = source_files
+            .iter()
+            .filter(|file| Some(file.as_str()) != preview)
+            .filter(|file| {
+                !option_folders
+                    .iter()
+                    .any(|folder| file.starts_with(folder.as_str()))
+            })
+            .map(|file| (file.clone(), file.clone()))
+            .collect();
+
+        for group in option_groups {
+            let folder = match group.selected_choice(selected_options) {
+                Some(choice) => choice.folder_prefix(),
+                None => continue,
+            };
+
+            for file in source_files {
+                if let Some(path) = file.strip_prefix(folder.as_str()) {
+                    if !path.is_empty() {
+                        files.insert(path.to_owned(), file.clone());
+                    }
+                }
+            }
+        }
+
+        if !file_rules.is_empty() {
+            files = apply_rules(files, file_rules);
+        }
+
+        files.retain(|file, source| {
+            let inside = is_relative_path(file);
+
+            if !inside {
+                log::warn!(
+                    "Skipping the file '{}' of the mod, as it would be placed outside of the game at '{}'",
+                    source,
+                    file
+                );
+            }
+
+            inside
+        });
+
+        Self { files }
+    }
+
+    /// Paths of the files inside the game
+    pub fn files(&self) -> Vec<String> {
+        self.files.keys().cloned().collect()
+    }
+
+    /// Paths of the dirs inside the game which contain the files, including the trailing slash. Parent dirs are listed before their children.
+    pub fn dirs(&self) -> Vec<String> {
+        let mut dirs = BTreeSet::new();
+
+        for file in self.files.keys() {
+            for (index, _) in file.match_indices('/') {
+                dirs.insert(file[..=index].to_owned());
+            }
+        }
+
+        dirs.into_iter().collect()
+    }
+
+    /// Path inside the mod source of the file with the provided path inside the game
+    pub fn source_path<'a>(&'a self, file: &'a str) -> &'a str {
+        self.files.get(file).map_or(file, String::as_str)
+    }
+
+    /// Keys the hashes of the source files by the paths inside the game
+    pub fn map_hashes(&self, source_hashes: &HashMap<String, String>) -> HashMap<String, String> {
+        self.files
+            .iter()
+            .filter_map(|(file, source)| {
+                source_hashes
+                    .get(source)
+                    .map(|hash| (file.clone(), hash.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Places the files matching the rules into their target folders. The source paths of the files are kept.
+fn apply_rules(files: BTreeMap<String, String>, rules: &[FileRule]) -> BTreeMap<String, String> {
+    let rules: Vec<(Pattern, String)> = rules
+        .iter()
+        .map(|rule| (Pattern::new(&rule.source), rule.target_prefix()))
+        .collect();
+
+    let mut mapped = BTreeMap::new();
+
+    for (file, source) in files {
+        let mut matched = false;
+
+        for (pattern, target) in rules.iter().filter(|(pattern, _)| pattern.matches(&file)) {
+            mapped.insert(
+                format!("{}{}", target, pattern.strip_base(&file)),
+                source.clone(),
+            );
+            matched = true;
+        }
+
+        if !matched {
+            mapped.insert(file, source);
+        }
+    }
+
+    mapped
+}
+
+/// If the path stays inside of its root folder, so it is neither absolute nor leaves the folder with `..`
+pub fn is_relative_path(path: &str) -> bool {
+    let path = path.replace('\\', "/");
+
+    !path.starts_with('/')
+        && !path.contains(':')
+        && !path.split('/').any(|component| component == "..")
+}
@@ -0,0 +1,180 @@
+//! Migrations of the database data written by older modloader versions
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sled::transaction::{ConflictableTransactionError, TransactionError};
+use sled::Transactional;
+
+use crate::config::{ModloaderConfig, LEGACY_INSTALLATION_NAME};
+use crate::db::{BincodeDb, Key};
+use crate::DB;
+
+use super::archive::{self, ArchiveFormat};
+use super::error::{ModManagerError, Result};
+use super::filetree::{FileTreeManager, DB_FILE_TREE_NAME};
+use super::registry::{LegacyMod, Mod, DB_MOD_TREE_NAME};
+
+const DB_META_TREE_NAME: &str = "meta";
+const DB_VERSION_KEY: &str = "db_version";
+/// The current version of the data layout in the DB
+const DB_VERSION: u32 = 1;
+/// Mods migrated from the v0.1.0 layout, keyed like the mod tree. The value is the path of the legacy archive of the mod.
+const DB_MIGRATED_V0_TREE_NAME: &str = "migrated_v0";
+
+/// Migrates the data in the DB to the current [`DB_VERSION`] if required
+pub fn migrate_db(config: &ModloaderConfig) -> Result<()> {
+    let meta_tree = DB.open_tree(DB_META_TREE_NAME);
+    let version_key = Key::<u32>::new(DB_VERSION_KEY);
+
+    let version = meta_tree.b_get(&version_key)?.unwrap_or(0);
+
+    if version == 0 {
+        migrate_from_v0(config)?;
+    }
+
+    meta_tree.b_insert(&version_key, &DB_VERSION)?;
+
+    // The markers of the migrated mods are only needed to resume an interrupted migration
+    DB.get_inner().drop_tree(DB_MIGRATED_V0_TREE_NAME)?;
+
+    Ok(())
+}
+
+/// Migrates the DB of modloader v0.1.0, which only knew a single game installation.
+///
+/// All active mods and the file tree are moved into the [`LEGACY_INSTALLATION_NAME`] installation, which is created by the config migration.
+///
+/// Every mod is migrated on its own and marked as migrated together with writing the migrated mod, so an interrupted migration continues with the remaining mods on the next start.
+fn migrate_from_v0(config: &ModloaderConfig) -> Result<()> {
+    let mod_tree = DB.open_tree(DB_MOD_TREE_NAME);
+    let migrated_tree = DB.open_tree(DB_MIGRATED_V0_TREE_NAME);
+
+    for entry in mod_tree.iter() {
+        let (key, value) = entry?;
+
+        // Mods which have already been migrated by an interrupted migration only need to finish the steps after writing the migrated mod
+        if let Some(legacy_archive_path) = migrated_tree.get(&key)? {
+            let modification = bincode::deserialize::<Mod>(&value)?;
+            let legacy_archive_path = bincode::deserialize::<PathBuf>(&legacy_archive_path)?;
+
+            // The copy into the registry may have been interrupted before the mod was marked
+            copy_to_registry(&legacy_archive_path, &modification)?;
+            modification.index_identities()?;
+            remove_legacy_archive(&legacy_archive_path, &modification)?;
+
+            continue;
+        }
+
+        let legacy_modification = bincode::deserialize::<LegacyMod>(&value)?;
+
+        let archive_path = legacy_modification.archive_path();
+        // Without fingerprint the archive can not be stored in the registry, so the migration is retried on the next start
+        let fingerprint = archive::hash_file(&archive_path).map_err(|e| {
+            log::error!(
+                "Failed to fingerprint mod archive {:?}: {:?}",
+                archive_path,
+                e
+            );
+            e
+        })?;
+
+        let archive_format = ArchiveFormat::detect(&archive_path)
+            .unwrap_or_else(|_| legacy_modification.archive_format_from_extension());
+
+        let modification: Mod =
+            legacy_modification.migrate(LEGACY_INSTALLATION_NAME, &fingerprint, archive_format);
+
+        copy_to_registry(&archive_path, &modification)?;
+
+        let migrated_value = bincode::serialize(&modification)?;
+        let marker_value = bincode::serialize(&archive_path)?;
+
+        (&mod_tree, &migrated_tree)
+            .transaction(|(mods, migrated)| {
+                mods.insert(&key, migrated_value.clone())?;
+                migrated.insert(&key, marker_value.clone())?;
+
+                Ok::<_, ConflictableTransactionError>(())
+            })
+            .map_err(|error: TransactionError| match error {
+                TransactionError::Storage(error) => ModManagerError::from(error),
+                TransactionError::Abort(()) => ModManagerError::Db {
+                    msg: "Migration of a mod has been aborted".to_string(),
+                },
+            })?;
+
+        modification.index_identities()?;
+        remove_legacy_archive(&archive_path, &modification)?;
+    }
+
+    // Files of the legacy file tree are all placed in the game language of the legacy installation
+    if let Some(installation) = config.get_installation(LEGACY_INSTALLATION_NAME) {
+        let legacy_file_tree = DB.open_tree(DB_FILE_TREE_NAME);
+        let file_tree = DB.open_tree(&FileTreeManager::tree_name(
+            LEGACY_INSTALLATION_NAME,
+            installation.get_language(),
+        ));
+
+        for entry in legacy_file_tree.iter() {
+            let (key, value) = entry?;
+
+            file_tree.insert(key, value)?;
+        }
+    }
+
+    DB.get_inner().drop_tree(DB_FILE_TREE_NAME)?;
+
+    log::info!("Migrated DB from modloader v0.1.0 layout");
+
+    Ok(())
+}
+
+/// Copies the legacy archive of a mod into the registry, which stores the archives under their fingerprint. Mods with the same content share their archive.
+///
+/// The archive is copied to a temporary file first, so an interrupted copy never leaves a truncated archive in the registry. An archive already in the registry is only kept if its content matches the fingerprint. Legacy archives which have already been removed by an interrupted migration are skipped.
+fn copy_to_registry(legacy_archive_path: &Path, modification: &Mod) -> Result<()> {
+    let archive_path = modification.archive_path();
+
+    if modification.fingerprint().is_empty()
+        || legacy_archive_path == archive_path
+        || !legacy_archive_path.is_file()
+        || is_intact(&archive_path, modification)
+    {
+        return Ok(());
+    }
+
+    let temp_path = archive_path.with_extension("part");
+    fs::copy(legacy_archive_path, &temp_path)?;
+    fs::rename(&temp_path, &archive_path)?;
+
+    Ok(())
+}
+
+/// Whether the archive exists and its content matches the fingerprint of the mod
+fn is_intact(archive_path: &Path, modification: &Mod) -> bool {
+    archive_path.is_file()
+        && matches!(archive::hash_file(archive_path), Ok(hash) if hash == modification.fingerprint())
+}
+
+/// Removes the legacy archive of a migrated mod. The legacy archive is only removed once the migrated mod points to an intact copy.
+fn remove_legacy_archive(legacy_archive_path: &Path, modification: &Mod) -> Result<()> {
+    if modification.fingerprint().is_empty()
+        || legacy_archive_path == modification.archive_path()
+        || !legacy_archive_path.is_file()
+    {
+        return Ok(());
+    }
+
+    if !is_intact(&modification.archive_path(), modification) {
+        log::warn!(
+            "Keeping legacy archive {:?}, as its copy in the registry does not match the fingerprint of mod {}",
+            legacy_archive_path,
+            modification.name
+        );
+        return Ok(());
+    }
+
+    fs::remove_file(legacy_archive_path)?;
+
+    Ok(())
+}
@@ -1,43 +1,112 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
-use tauri::Window;
-use tokio::sync::oneshot;
+use tauri::{AppHandle, Window};
+use tokio::sync::{oneshot, Mutex};
 
-use crate::config::APP_SAVE_PATH;
+use crate::config::{self, ConfigError, ConfigManager, GameInstallation, WatchFolder, DATA_PATH};
 use crate::DB;
 
 mod archive;
+mod authoring;
+mod changelog;
+mod directory;
 pub mod error;
+mod export;
 mod filetree;
+mod glob;
+mod import;
 mod injection;
+mod layout;
+mod lint;
+mod localized;
+mod mapping;
+mod migration;
+mod options;
+mod pack;
+mod preview;
 mod registry;
+mod source;
+mod watch;
 
-use archive::ModArchive;
+use archive::{FileDiff, FileDiffSummary, ModArchive};
+use changelog::{Changelog, ChangelogEntry};
+use directory::ModDirectory;
 use error::{ModManagerError, Result};
 use filetree::FileTreeManager;
+use import::{AddedMod, OverwriteResolver};
 use injection::InjectionType;
+use mapping::FileMapping;
+use pack::{PackManifest, PACK_MANIFEST_FILE};
+use source::ModSource;
+use watch::FolderWatcher;
 
-pub use registry::{Mod, DB_MOD_TREE_NAME, MOD_REGISTRY_PATH};
+use registry::VersionChange;
+
+pub use archive::ModInfo;
+pub use authoring::{PackageMetadata, PackageReport};
+pub use export::ExportFormat;
+pub use import::{AddModResult, ImportReport, OverwritePolicy};
+pub use lint::Diagnostic;
+pub use registry::{Mod, ModVersion, DB_MOD_TREE_NAME, MOD_REGISTRY_PATH};
+
+/// Number of archives which are read at the same time during an import
+const IMPORT_CONCURRENCY: usize = 4;
+/// Folder inside the temp dir of the system used for packing and extracting mods
+const TEMP_FOLDER: &str = "aw-modloader";
+/// Number of packaged mods of this process, which keeps the package folders of concurrent packaging apart
+static PACKAGE_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 #[derive(Debug, Serialize, Deserialize)]
 struct OverwriteEventPayload {
     overwrite: bool,
 }
 
-pub struct ModManager;
+/// Sent to the frontend to ask the user whether an already existing mod should be replaced
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct AskOverwriteEventPayload {
+    name: String,
+    installed_version: Option<String>,
+    new_version: Option<String>,
+    change: VersionChange,
+    /// Changelog entries between the installed and the new version, newest first
+    changelog: Vec<ChangelogEntry>,
+    /// Changes of the mod files between the installed and the new version
+    files: FileDiffSummary,
+    /// If the answer is applied to all further existing mods of the import
+    batch: bool,
+}
+
+pub struct ModManager {
+    config: ConfigManager,
+    /// Serializes the registration of mods, so concurrently added versions of the same mod can not interfere
+    registration_lock: Mutex<()>,
+    /// Watcher of the configured watch folder, None if no watch folder is configured
+    folder_watcher: std::sync::Mutex<Option<FolderWatcher>>,
+}
 
 impl ModManager {
     /// Creates the ModManager and performs the necessary initialization
-    pub fn new() -> Result<Self> {
-        fs::create_dir_all(APP_SAVE_PATH.join(MOD_REGISTRY_PATH))?;
+    pub fn new(config: ConfigManager) -> Result<Self> {
+        fs::create_dir_all(DATA_PATH.join(MOD_REGISTRY_PATH))?;
+
+        migration::migrate_db(&tauri::async_runtime::block_on(config.read()))?;
 
-        Ok(Self)
+        Ok(Self {
+            config,
+            registration_lock: Mutex::new(()),
+            folder_watcher: std::sync::Mutex::new(None),
+        })
     }
 
-    pub fn get_initial_mod_data(&self) -> Result<HashMap<u64, Mod>> {
+    /// All registered mods, with their name and info translated into the app language
+    pub async fn get_initial_mod_data(&self) -> Result<HashMap<u64, Mod>> {
         let tree = DB.open_tree(DB_MOD_TREE_NAME);
+        let language = self.config.read().await.get_app_language();
 
         let mut hashmap = HashMap::new();
 
@@ -48,7 +117,8 @@ impl ModManager {
                 .map_err(|e| ModManagerError::DeSerialization { msg: e.to_string() })?
                 .parse::<u64>()
                 .map_err(|e| ModManagerError::DeSerialization { msg: e.to_string() })?;
-            let modification: Mod = bincode::deserialize(&modification)?;
+            let mut modification: Mod = bincode::deserialize(&modification)?;
+            modification.localize(language.as_deref());
 
             hashmap.insert(key, modification);
         }
@@ -56,97 +126,994 @@ impl ModManager {
         Ok(hashmap)
     }
 
+    /// Translate the name and info of the mod into the app language before it is shown to the user
+    pub async fn localize(&self, modification: &mut Mod) {
+        let language = self.config.read().await.get_app_language();
+
+        modification.localize(language.as_deref());
+    }
+
     /// Add a new mod to the registry
-    pub async fn add_mod(&self, archive_path: &str, window: Window) -> Result<()> {
+    ///
+    /// Mod packs are split into their mods, which are added individually.
+    pub async fn add_mod(&self, archive_path: &str, window: Window) -> Result<AddModResult> {
         let mod_archive = ModArchive::open(archive_path).await?;
+        let overwrite_resolver = OverwriteResolver::new(OverwritePolicy::Ask);
+
+        if let Some(folders) = mod_archive.detect_pack_folders().await? {
+            let mut report = ImportReport::default();
+
+            for (path, result) in self
+                .register_pack(&mod_archive, folders, &overwrite_resolver, window)
+                .await?
+            {
+                report.record(path, result);
+            }
+
+            return Ok(AddModResult::Pack(report));
+        }
 
         log::debug!(
             "Adding mod with archive name {} to registry",
             mod_archive.name
         );
 
-        let mod_info = mod_archive.get_modinfo().await?;
+        let added_mod = self
+            .register_mod(ModSource::Archive(mod_archive), &overwrite_resolver, window)
+            .await?;
+
+        Ok(AddModResult::Mod {
+            path_prefix: added_mod.and_then(|added_mod| added_mod.path_prefix),
+        })
+    }
+
+    /// Import multiple mod archives at once. Folders are searched for archives, without descending into subfolders. Folders which can not be read are listed as failed imports.
+    ///
+    /// The archives are read concurrently and existing mods are handled according to the overwrite policy. Archives which fail to import do not stop the import but are listed in the report.
+    pub async fn import_mods(
+        &self,
+        paths: Vec<String>,
+        policy: OverwritePolicy,
+        window: Window,
+    ) -> Result<ImportReport> {
+        self.import_mods_with(paths, &OverwriteResolver::new(policy), window)
+            .await
+    }
+
+    /// Imports the provided archives like [`Self::import_mods()`], deciding about existing mods with the provided resolver
+    ///
+    /// The resolver can be shared between several imports, so an answer to [`OverwritePolicy::AskOnce`] applies to all of them.
+    async fn import_mods_with(
+        &self,
+        paths: Vec<String>,
+        resolver: &OverwriteResolver,
+        window: Window,
+    ) -> Result<ImportReport> {
+        let (archive_paths, failed) =
+            tauri::async_runtime::spawn_blocking(move || import::collect_archive_paths(paths))
+                .await?;
+
+        log::info!("Importing {} mod archives", archive_paths.len());
+
+        let results: Vec<Vec<(String, Result<Option<AddedMod>>)>> = stream::iter(archive_paths)
+            .map(|path| {
+                let window = window.clone();
+
+                async move {
+                    match self.import_archive(&path, resolver, window).await {
+                        Ok(results) => results,
+                        Err(err) => vec![(path, Err(err))],
+                    }
+                }
+            })
+            .buffer_unordered(IMPORT_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut report = ImportReport {
+            failed,
+            ..Default::default()
+        };
+
+        for (path, result) in results.into_iter().flatten() {
+            report.record(path, result);
+        }
+
+        Ok(report)
+    }
+
+    /// Add the mods of an archive to the registry. Returns the outcome of every mod, mod packs are split into their mods.
+    async fn import_archive(
+        &self,
+        archive_path: &str,
+        overwrite_resolver: &OverwriteResolver,
+        window: Window,
+    ) -> Result<Vec<(String, Result<Option<AddedMod>>)>> {
+        let mod_archive = ModArchive::open(archive_path).await?;
+
+        if let Some(folders) = mod_archive.detect_pack_folders().await? {
+            return self
+                .register_pack(&mod_archive, folders, overwrite_resolver, window)
+                .await;
+        }
+
+        let result = self
+            .register_mod(ModSource::Archive(mod_archive), overwrite_resolver, window)
+            .await;
+
+        Ok(vec![(archive_path.to_owned(), result)])
+    }
+
+    /// Splits a mod pack into its mods and adds them to the registry individually
+    ///
+    /// Every mod folder is packed into an archive of its own, so each mod gets its own registry archive. Returns the outcome of every mod, keyed by the path of the pack followed by the mod folder.
+    async fn register_pack(
+        &self,
+        mod_archive: &ModArchive,
+        folders: Vec<String>,
+        overwrite_resolver: &OverwriteResolver,
+        window: Window,
+    ) -> Result<Vec<(String, Result<Option<AddedMod>>)>> {
+        log::info!(
+            "Splitting mod pack {} into {} mods",
+            mod_archive.name,
+            folders.len()
+        );
+
+        let pack_dir = std::env::temp_dir()
+            .join(TEMP_FOLDER)
+            .join(format!("pack-{}", DB.get_inner().generate_id()?));
+        let content_dir = pack_dir.join("content");
+        fs::create_dir_all(&content_dir)?;
+
+        let result = async {
+            mod_archive.extract(&content_dir).await?;
+
+            let mut results = vec![];
+
+            for (index, folder) in folders.iter().enumerate() {
+                let path = format!(
+                    "{}/{}",
+                    mod_archive.path.to_string_lossy(),
+                    folder.trim_end_matches('/')
+                );
+
+                // Every archive gets its own folder, as mod folders in different parts of the pack can have the same name
+                let archive_dir = pack_dir.join(index.to_string());
+
+                let result = self
+                    .register_pack_folder(
+                        &content_dir.join(folder),
+                        &archive_dir,
+                        overwrite_resolver,
+                        window.clone(),
+                    )
+                    .await;
+
+                results.push((path, result));
+            }
+
+            Ok::<_, ModManagerError>(results)
+        }
+        .await;
+
+        if let Err(err) = fs::remove_dir_all(&pack_dir) {
+            log::warn!(
+                "Failed to remove extracted mod pack {:?}: {:?}",
+                pack_dir,
+                err
+            );
+        }
+
+        result
+    }
+
+    /// Packs a mod folder of an extracted mod pack into an archive inside the archive dir and adds it to the registry
+    async fn register_pack_folder(
+        &self,
+        folder_path: &Path,
+        archive_dir: &Path,
+        overwrite_resolver: &OverwriteResolver,
+        window: Window,
+    ) -> Result<Option<AddedMod>> {
+        let mod_directory = ModDirectory::open(folder_path).await?;
+
+        // The archive is named after the folder, as its name is used for mods without modinfo.json
+        fs::create_dir_all(archive_dir)?;
+        let archive_path = archive_dir.join(format!("{}.zip", mod_directory.name));
+
+        mod_directory.pack(&archive_path).await?;
+
+        let mod_archive = ModArchive::open(&archive_path).await?;
+
+        self.register_mod(ModSource::Archive(mod_archive), overwrite_resolver, window)
+            .await
+    }
+
+    /// Export the current versions of the mods as archive to the provided path
+    ///
+    /// A single mod is exported as mod archive, multiple mods are exported as mod pack with a manifest, each mod in its own folder.
+    pub async fn export_mods(
+        &self,
+        uids: Vec<u64>,
+        format: ExportFormat,
+        target_path: &str,
+    ) -> Result<()> {
+        let mods = uids
+            .into_iter()
+            .map(Mod::get_from_db)
+            .collect::<Result<Vec<Mod>>>()?;
+
+        log::info!("Exporting {} mods to {}", mods.len(), target_path);
+
+        let export_dir = std::env::temp_dir()
+            .join(TEMP_FOLDER)
+            .join(format!("export-{}", DB.get_inner().generate_id()?));
+
+        let result = async {
+            if let [modification] = mods.as_slice() {
+                export::write_mod(modification, &export_dir).await?;
+            } else {
+                let folders = export::pack_folder_names(&mods);
+
+                for (modification, folder) in mods.iter().zip(&folders) {
+                    export::write_mod(modification, &export_dir.join(folder)).await?;
+                }
+
+                let manifest = PackManifest { mods: folders };
+                fs::write(
+                    export_dir.join(PACK_MANIFEST_FILE),
+                    serde_json::to_vec_pretty(&manifest)?,
+                )?;
+            }
+
+            export::pack_folder(&export_dir, format, Path::new(target_path)).await
+        }
+        .await;
+
+        if let Err(err) = fs::remove_dir_all(&export_dir) {
+            log::warn!("Failed to remove export folder {:?}: {:?}", export_dir, err);
+        }
+
+        result
+    }
+
+    /// Reads the modinfo.json file of a mod folder, None if the folder does not contain one
+    pub async fn get_folder_mod_info(&self, folder_path: &str) -> Result<Option<ModInfo>> {
+        ModDirectory::open(folder_path).await?.get_modinfo().await
+    }
+
+    /// Lints the modinfo.json file at the path, or the modinfo.json file of the mod folder at the path
+    ///
+    /// Returns all problems of the file. Fails if there is no modinfo.json file.
+    pub async fn lint_mod_info(path: &str) -> Result<Vec<Diagnostic>> {
+        let mod_info_file = if Path::new(path).is_dir() {
+            ModDirectory::open(path)
+                .await?
+                .get_modinfo_file()
+                .await?
+                .ok_or_else(|| ModManagerError::InvalidModInfo {
+                    msg: String::from("The folder does not contain a modinfo.json file"),
+                })?
+        } else {
+            let path = path.to_owned();
+            tauri::async_runtime::spawn_blocking(move || fs::read(path)).await??
+        };
+
+        Ok(lint::lint(&String::from_utf8_lossy(&mod_info_file)))
+    }
+
+    /// Package a mod folder into an archive which follows the required mod archive structure
+    ///
+    /// The modinfo.json is built from the metadata and the modinfo.json file of the folder and validated. A misplaced folder layout is fixed and the folders of the mod are checked against the game folder of the selected installation.
+    pub async fn package_mod(
+        &self,
+        folder_path: &str,
+        metadata: PackageMetadata,
+        format: ExportFormat,
+        target_path: &str,
+    ) -> Result<PackageReport> {
+        let localization_path = self.get_localization_path().await;
+
+        Self::package_folder(
+            folder_path,
+            metadata,
+            format,
+            target_path,
+            localization_path.as_deref(),
+        )
+        .await
+    }
+
+    /// Package a mod folder into an archive, checking the folders of the mod against the provided game folder
+    ///
+    /// This does not access the DB, so the command line can package mods while the app is running.
+    pub async fn package_folder(
+        folder_path: &str,
+        metadata: PackageMetadata,
+        format: ExportFormat,
+        target_path: &str,
+        localization_path: Option<&Path>,
+    ) -> Result<PackageReport> {
+        let mod_directory = ModDirectory::open(folder_path).await?;
+
+        let mod_info = metadata.into_mod_info(mod_directory.get_modinfo().await?)?;
+        mod_info.validate()?;
+
+        let path_prefix = mod_directory
+            .detect_layout_prefix(mod_info.files.is_some(), localization_path)
+            .await?;
+        let prefix = path_prefix.clone().unwrap_or_default();
+        let (files, _) = mod_directory.get_dirs_and_files(&prefix).await?;
+        // Unknown folders are reported by their paths inside the game
+        let files = FileMapping::new(
+            &files,
+            None,
+            mod_info.options.as_deref().unwrap_or_default(),
+            &BTreeMap::new(),
+            mod_info.files.as_deref().unwrap_or_default(),
+        )
+        .files();
+
+        let report = PackageReport {
+            unknown_folders: authoring::unknown_game_folders(&files, localization_path),
+            path_prefix,
+        };
+
+        log::info!(
+            "Packaging mod folder {:?} into {}",
+            mod_directory.path,
+            target_path
+        );
+
+        // The ids of the DB can not be used, as the DB is locked while the app is running
+        let package_dir = std::env::temp_dir().join(TEMP_FOLDER).join(format!(
+            "package-{}-{}",
+            std::process::id(),
+            PACKAGE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let result = async {
+            export::write_source(
+                ModSource::Directory(mod_directory),
+                &prefix,
+                Some(mod_info),
+                &package_dir,
+            )
+            .await?;
+
+            export::pack_folder(&package_dir, format, Path::new(target_path)).await
+        }
+        .await;
+
+        if let Err(err) = fs::remove_dir_all(&package_dir) {
+            log::warn!(
+                "Failed to remove package folder {:?}: {:?}",
+                package_dir,
+                err
+            );
+        }
+
+        result.map(|_| report)
+    }
+
+    /// Starts watching the configured watch folder for new mod archives, replacing any previously watched folder
+    ///
+    /// Only archives which are added to the folder while it is watched are imported.
+    pub async fn watch_folder(&self, app_handle: AppHandle) -> Result<()> {
+        let watch_folder = self.config.read().await.get_watch_folder().cloned();
+
+        let folder_watcher = match watch_folder {
+            Some(watch_folder) => Some(FolderWatcher::start(app_handle, &watch_folder)?),
+            None => None,
+        };
+
+        *self
+            .folder_watcher
+            .lock()
+            .expect("Folder watcher mutex has been poisoned") = folder_watcher;
+
+        Ok(())
+    }
+
+    /// Configure the folder which is watched for new mod archives. None stops watching.
+    pub async fn set_watch_folder(
+        &self,
+        watch_folder: Option<WatchFolder>,
+        app_handle: AppHandle,
+    ) -> Result<()> {
+        self.config
+            .write()
+            .await
+            .set_watch_folder(watch_folder)
+            .await?;
+
+        self.watch_folder(app_handle).await
+    }
+
+    /// Add a new mod from an unpacked folder
+    ///
+    /// The folder is either packed into an archive which is added to the registry like any other mod archive, or added as development mod whose files are read directly from the folder.
+    /// Returns the path prefix which is stripped from the paths if the folder layout had to be fixed. Packed folders which contain a mod pack are split into their mods.
+    pub async fn add_mod_directory(
+        &self,
+        directory_path: &str,
+        development: bool,
+        window: Window,
+    ) -> Result<AddModResult> {
+        let mod_directory = ModDirectory::open(directory_path).await?;
+
+        if development {
+            log::debug!(
+                "Adding mod folder {:?} as development mod",
+                mod_directory.path
+            );
+
+            let added_mod = self
+                .register_mod(
+                    ModSource::Directory(mod_directory),
+                    &OverwriteResolver::new(OverwritePolicy::Ask),
+                    window,
+                )
+                .await?;
+
+            return Ok(AddModResult::Mod {
+                path_prefix: added_mod.and_then(|added_mod| added_mod.path_prefix),
+            });
+        }
+
+        log::debug!(
+            "Packing mod folder {:?} into an archive",
+            mod_directory.path
+        );
+
+        // The archive is named after the folder, as its name is used for mods without modinfo.json. Each archive gets its own folder, so folders with the same name can be added at the same time.
+        let pack_dir = std::env::temp_dir()
+            .join(TEMP_FOLDER)
+            .join(format!("folder-{}", DB.get_inner().generate_id()?));
+        fs::create_dir_all(&pack_dir)?;
+        let archive_path = pack_dir.join(format!("{}.zip", mod_directory.name));
+
+        let result = async {
+            mod_directory.pack(&archive_path).await?;
+
+            self.add_mod(&archive_path.to_string_lossy(), window).await
+        }
+        .await;
+
+        if let Err(err) = fs::remove_dir_all(&pack_dir) {
+            log::warn!(
+                "Failed to remove packed archive {:?}: {:?}",
+                archive_path,
+                err
+            );
+        }
+
+        result
+    }
+
+    /// Reads the mod with all of its metadata from the source, together with its changelog
+    async fn read_mod(&self, mod_source: &ModSource) -> Result<(Mod, Changelog)> {
+        let mod_info = mod_source.get_modinfo().await?;
+        let fingerprint = mod_source.get_fingerprint().await?;
+
+        let has_file_rules = mod_info
+            .as_ref()
+            .map_or(false, |mod_info| mod_info.files.is_some());
+        let localization_path = self.get_localization_path().await;
+        let path_prefix = mod_source
+            .detect_layout_prefix(has_file_rules, localization_path.as_deref())
+            .await?;
+
+        if let Some(path_prefix) = &path_prefix {
+            log::info!(
+                "Mod {} has a misplaced layout, stripping prefix '{}'",
+                mod_source.name(),
+                path_prefix
+            );
+        }
+
+        // The changelog of the modinfo.json takes precedence over a CHANGELOG.md file
+        let changelog = match mod_info
+            .as_ref()
+            .and_then(|mod_info| mod_info.changelog.clone())
+        {
+            Some(changelog) => Changelog::from_map(changelog)?,
+            None => mod_source
+                .get_changelog_file()
+                .await?
+                .map(|changelog| Changelog::from_markdown(&changelog))
+                .unwrap_or_default(),
+        };
+
+        let declared_preview = mod_info
+            .as_ref()
+            .and_then(|mod_info| mod_info.preview.clone());
 
         let mut modification;
 
         if mod_info.is_none() {
             modification = Mod::new(
-                &mod_archive.name,
+                mod_source.name(),
                 InjectionType::Localization,
-                &mod_archive.extension,
+                mod_source.format(),
+                &fingerprint,
+                path_prefix,
             )?;
         } else {
-            modification = Mod::from_mod_info(mod_info.unwrap(), &mod_archive.extension)?;
+            modification = Mod::from_mod_info(
+                mod_info.unwrap(),
+                mod_source.format(),
+                &fingerprint,
+                path_prefix,
+            )?;
+        }
+
+        if let ModSource::Directory(mod_directory) = mod_source {
+            modification.set_development_path(mod_directory.path.clone());
         }
 
+        let (files, _) = mod_source
+            .get_dirs_and_files(modification.path_prefix())
+            .await?;
+        modification.set_preview(preview::find_preview(&files, declared_preview.as_deref()));
+
+        Ok((modification, changelog))
+    }
+
+    /// Add the mod of the source to the registry. If the mod already exists the overwrite resolver decides whether it is replaced, asking the user if necessary.
+    ///
+    /// Returns None if the existing mod has been kept.
+    async fn register_mod(
+        &self,
+        mod_source: ModSource,
+        overwrite_resolver: &OverwriteResolver,
+        window: Window,
+    ) -> Result<Option<AddedMod>> {
+        let (mut modification, changelog) = self.read_mod(&mod_source).await?;
+
+        let path_prefix = Some(modification.path_prefix().to_owned())
+            .filter(|path_prefix| !path_prefix.is_empty());
+
+        let _registration = self.registration_lock.lock().await;
+
         // Check if mod already exists in registry
-        if let Some(existing_modification) = modification.is_already_existing().await? {
-            if modification.is_newer_version(&existing_modification) {
-                // ask user for overwrite permission
-                let (oneshot_sender, oneshot_receiver) = oneshot::channel();
-                window.once("add-mod-overwrite", |event| {
-                    let overwrite: OverwriteEventPayload =
-                        serde_json::from_str(event.payload().expect(
-                            "Received None as event payload but expected payload to contain value",
-                        ))
-                        .expect("Expected OverwriteEventPayload but failed to deserialize");
-
-                    oneshot_sender.send(overwrite.overwrite).expect(
-                        "Oneshot receiver has been dropped before sender could send the value",
-                    );
-                });
+        let existing_modification = modification.is_already_existing().await?;
+        let updated = existing_modification.is_some();
+        let mut hashes = None;
+
+        if let Some(existing_modification) = &existing_modification {
+            if overwrite_resolver.decision() == Some(false) {
+                log::info!("Keeping existing mod {}", existing_modification.name);
+                return Ok(None);
+            }
 
-                window.emit("add-mod-ask-overwrite", &modification.name)?;
+            // The selected options decide which files of the new version are injected
+            modification.inherit_user_settings(existing_modification);
 
-                let overwrite = oneshot_receiver
-                    .await
-                    .expect("Oneshot sender has been dropped bevore a value could be received");
+            let new_hashes = modification.get_file_hashes(&mod_source).await?;
+            let old_hashes = self.get_registry_file_hashes(existing_modification).await;
+
+            if overwrite_resolver.decision().is_none() {
+                let changelog_entries =
+                    match (&existing_modification.version, &modification.version) {
+                        (Some(installed_version), Some(new_version)) => {
+                            changelog.entries_between(installed_version, new_version)
+                        }
+                        _ => vec![],
+                    };
+
+                // ask user for overwrite permission, showing which kind of version change this is
+                let overwrite = Self::ask_overwrite(
+                    &window,
+                    AskOverwriteEventPayload {
+                        name: modification.name.clone(),
+                        installed_version: existing_modification
+                            .version
+                            .as_ref()
+                            .map(|version| version.to_string()),
+                        new_version: modification
+                            .version
+                            .as_ref()
+                            .map(|version| version.to_string()),
+                        change: modification.version_change(existing_modification),
+                        changelog: changelog_entries,
+                        files: FileDiff::from_hashes(&old_hashes, &new_hashes).summary(),
+                        batch: overwrite_resolver.asks_once(),
+                    },
+                )
+                .await?;
+
+                overwrite_resolver.remember(overwrite);
 
                 if !overwrite {
-                    return Ok(());
+                    return Ok(None);
                 }
-            } else {
-                return Err(ModManagerError::ModVersionMismatch {
-                    mismatch: (
-                        modification.version.unwrap().to_string(),
-                        existing_modification.version.unwrap().to_string(),
-                    ),
-                });
             }
 
-            // remove active mod files of old mod version
-            log::debug!(
-                "Removing all active mod files of the old mod version to install the new one"
-            );
+            modification.uid = existing_modification.uid;
 
-            if existing_modification.is_active() {
-                existing_modification
-                    .injection_type()
-                    .eject_mod(&existing_modification)
-                    .await?;
+            // The new version is stored in its own registry archive, so the old version can be restored later on
+            modification.keep_previous_versions(existing_modification);
+
+            hashes = Some((old_hashes, new_hashes));
+        }
+
+        // Development mods are read from their folder and not stored in the registry. The archive is saved before any game files are touched, so a failed save leaves the game unchanged.
+        let saved_archive = match &mod_source {
+            ModSource::Archive(mod_archive) => {
+                modification.save_in_registry(mod_archive.clone()).await?
             }
+            ModSource::Directory(_) => false,
+        };
 
-            modification.uid = existing_modification.uid;
+        let new_version = modification.clone();
+
+        let result: Result<AddedMod> = async {
+            if let (Some(existing_modification), Some((old_hashes, new_hashes))) =
+                (&existing_modification, &hashes)
+            {
+                // Active installations are updated in place, so the mod stays active with the new version
+                let installations = self.active_installations_of(existing_modification).await;
+
+                if !installations.is_empty() {
+                    log::debug!(
+                        "Updating the active mod files of the old mod version to the new version"
+                    );
+
+                    modification = self
+                        .hot_update(
+                            modification,
+                            &mod_source,
+                            &installations,
+                            existing_modification,
+                            old_hashes,
+                            new_hashes,
+                        )
+                        .await?;
+                }
+            }
+
+            modification.create_thumbnail(&mod_source).await;
+
+            let added_mod = AddedMod {
+                uid: modification.uid,
+                name: modification.name.clone(),
+                updated,
+                path_prefix,
+            };
+
+            Self::insert_with_thumbnail(modification).await?;
+
+            Ok(added_mod)
+        }
+        .await;
+
+        // The archive saved for the new version is not referenced by any mod if adding it failed
+        if let (Err(_), true) = (&result, saved_archive) {
+            if let Err(err) = new_version.delete_registry_archive() {
+                log::warn!(
+                    "Failed to delete the registry archive of the mod which could not be added: {:?}",
+                    err
+                );
+            }
         }
 
-        modification.save_in_registry(mod_archive).await?;
+        result.map(Some)
+    }
+
+    /// Asks the user whether an existing mod should be replaced and waits for the answer
+    async fn ask_overwrite(window: &Window, payload: AskOverwriteEventPayload) -> Result<bool> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel();
+        window.once("add-mod-overwrite", |event| {
+            let overwrite: OverwriteEventPayload =
+                serde_json::from_str(event.payload().expect(
+                    "Received None as event payload but expected payload to contain value",
+                ))
+                .expect("Expected OverwriteEventPayload but failed to deserialize");
+
+            oneshot_sender
+                .send(overwrite.overwrite)
+                .expect("Oneshot receiver has been dropped before sender could send the value");
+        });
+
+        window.emit("add-mod-ask-overwrite", payload)?;
+
+        Ok(oneshot_receiver
+            .await
+            .expect("Oneshot sender has been dropped bevore a value could be received"))
+    }
+
+    /// Reads the folder of a development mod again and applies the changes to all installations the mod is active in
+    ///
+    /// The metadata of the mod is updated from the modinfo.json file of the folder as well.
+    pub async fn sync_mod(&self, uid: u64) -> Result<()> {
+        let existing_modification = Mod::get_from_db(uid)?;
+
+        let development_path = existing_modification
+            .development_path()
+            .ok_or(ModManagerError::NotADevelopmentMod)?
+            .to_owned();
+
+        log::info!(
+            "Syncing development mod {} from folder {:?}",
+            existing_modification.name,
+            development_path
+        );
 
-        modification.insert_into_db().await?;
+        let mod_source = ModSource::Directory(ModDirectory::open(&development_path).await?);
+
+        let (mut modification, _) = self.read_mod(&mod_source).await?;
+
+        let _registration = self.registration_lock.lock().await;
+
+        // The mod might have been changed while the folder has been read
+        let existing_modification = Mod::get_from_db(uid)?;
+
+        if existing_modification.development_path() != Some(development_path.as_path()) {
+            return Err(ModManagerError::NotADevelopmentMod);
+        }
+
+        modification.uid = uid;
+        modification.inherit_user_settings(&existing_modification);
+
+        let installations = self.active_installations_of(&existing_modification).await;
+
+        if !installations.is_empty() {
+            // The previous content of the folder is unknown, so all files are written again
+            modification = self
+                .hot_update(
+                    modification,
+                    &mod_source,
+                    &installations,
+                    &existing_modification,
+                    &HashMap::new(),
+                    &HashMap::new(),
+                )
+                .await?;
+        }
+
+        modification.create_thumbnail(&mod_source).await;
+
+        let previous_thumbnail = existing_modification
+            .thumbnail()
+            .filter(|thumbnail| modification.thumbnail() != Some(*thumbnail));
+
+        Self::insert_with_thumbnail(modification).await?;
+
+        // The thumbnail of the previous preview image is not used anymore if the preview has changed
+        if let Some(thumbnail) = previous_thumbnail {
+            if let Err(err) = preview::delete_thumbnail(thumbnail) {
+                log::warn!("Failed to delete thumbnail {}: {:?}", thumbnail, err);
+            }
+        }
 
         Ok(())
     }
 
+    /// Inserts the mod into the DB. Its thumbnail is deleted if inserting fails, unless a previous version of the mod uses it as well.
+    async fn insert_with_thumbnail(modification: Mod) -> Result<()> {
+        let thumbnail = modification.unshared_thumbnail();
+
+        let result = modification.insert_into_db().await;
+
+        if let (Err(_), Some(thumbnail)) = (&result, thumbnail) {
+            if let Err(err) = preview::delete_thumbnail(&thumbnail) {
+                log::warn!("Failed to delete thumbnail {}: {:?}", thumbnail, err);
+            }
+        }
+
+        result
+    }
+
+    /// Updates the injected files of the provided active installations from the existing mod version to the new one
+    ///
+    /// All installations are checked for conflicts and missing languages first, so nothing is changed if the update can not be applied. The returned mod is not stored in the DB yet.
+    ///
+    /// If updating the files fails, the previous version is injected again into all installations which have already been touched.
+    async fn hot_update(
+        &self,
+        mut modification: Mod,
+        mod_source: &ModSource,
+        installations: &[GameInstallation],
+        previous_modification: &Mod,
+        old_hashes: &HashMap<String, String>,
+        new_hashes: &HashMap<String, String>,
+    ) -> Result<Mod> {
+        let mapping = modification.get_file_mapping(mod_source).await?;
+        let new_files = mapping.files();
+
+        let mut updates = vec![];
+        let mut conflict_list = vec![];
+
+        for installation in installations {
+            let target_languages = modification.target_languages(installation);
+            let mut changes = vec![];
+
+            // Languages which are no longer targeted by the new version still have their files removed
+            let mut languages = FileTreeManager::get_languages(installation.get_name());
+            languages.extend(target_languages.iter().cloned());
+            languages.sort();
+            languages.dedup();
+
+            for language in languages {
+                let old_files = FileTreeManager::get_files(
+                    installation.get_name(),
+                    &language,
+                    modification.uid,
+                )?;
+
+                let diff = if target_languages.contains(&language) {
+                    if !installation.get_localization_path(&language).is_dir() {
+                        return Err(ModManagerError::LanguageNotInstalled { language });
+                    }
+
+                    FileDiff::between(&old_files, &new_files, old_hashes, new_hashes)
+                } else {
+                    FileDiff {
+                        removed: old_files,
+                        ..Default::default()
+                    }
+                };
+
+                if let Some(conflicts) =
+                    FileTreeManager::get_conflicts(installation.get_name(), &language, &diff.added)?
+                {
+                    for (uid, conflicting_path) in conflicts {
+                        let conflicting_mod = Mod::get_from_db(uid)?;
+
+                        conflict_list.push((
+                            conflicting_mod.name,
+                            format!("{}/{}", language, conflicting_path),
+                        ));
+                    }
+                }
+
+                if !diff.is_empty() {
+                    changes.push((language, diff));
+                }
+            }
+
+            updates.push((installation, changes));
+        }
+
+        if !conflict_list.is_empty() {
+            return Err(ModManagerError::ModConflict {
+                conflict: conflict_list,
+            });
+        }
+
+        for (idx, (installation, changes)) in updates.into_iter().enumerate() {
+            let injection_type = modification.injection_type();
+
+            modification = match injection_type
+                .update_mod(
+                    modification,
+                    installation,
+                    mod_source,
+                    changes,
+                    mapping.clone(),
+                )
+                .await
+            {
+                Ok(modification) => modification,
+                Err(err) => {
+                    log::error!(
+                        "Failed to update mod {} in installation {}, restoring the previous version",
+                        previous_modification.name,
+                        installation.get_name()
+                    );
+
+                    // The failed installation has already been cleaned up by the update, the others contain the new version
+                    for updated_installation in &installations[..idx] {
+                        if let Err(err) = injection_type
+                            .eject_mod(previous_modification, updated_installation)
+                            .await
+                        {
+                            log::error!("Failed to remove the updated mod files: {:?}", err);
+                        }
+                    }
+
+                    for updated_installation in &installations[..=idx] {
+                        self.restore_version(previous_modification, updated_installation)
+                            .await;
+                    }
+
+                    return Err(err);
+                }
+            };
+        }
+
+        Ok(modification)
+    }
+
+    /// Injects the previous version of a mod again after updating its files failed. If this fails as well, the mod is marked inactive in the installation to match the game files.
+    async fn restore_version(&self, previous_modification: &Mod, installation: &GameInstallation) {
+        let mut modification = previous_modification.clone();
+        modification.mark_inactive(installation.get_name());
+
+        if let Err(err) = self.inject_into(modification, installation).await {
+            log::error!(
+                "Failed to restore the previous version of mod {} in installation {}: {:?}",
+                previous_modification.name,
+                installation.get_name(),
+                err
+            );
+
+            let deactivation = Mod::get_from_db(previous_modification.uid)
+                .and_then(|mut modification| modification.deactivate(installation.get_name()));
+
+            if let Err(err) = deactivation {
+                log::error!("Failed to mark the mod inactive: {:?}", err);
+            }
+        }
+    }
+
+    /// Switches back to the previous version after activating another version failed and activates it again in the provided installations
+    ///
+    /// Restoring is best effort, every installation the previous version can not be activated in is left with the mod deactivated.
+    async fn restore_switched_version(
+        &self,
+        uid: u64,
+        previous_fingerprint: &str,
+        installations: &[GameInstallation],
+    ) {
+        for installation in installations {
+            let is_active = Mod::get_from_db(uid)
+                .map(|modification| modification.is_active_in(installation.get_name()));
+
+            let deactivation = match is_active {
+                Ok(true) => self.deactivate_mod_in(uid, installation).await,
+                Ok(false) => Ok(()),
+                Err(err) => Err(err),
+            };
+
+            if let Err(err) = deactivation {
+                log::warn!(
+                    "Failed to deactivate the new version of mod {} in installation {}: {:?}",
+                    uid,
+                    installation.get_name(),
+                    err
+                );
+            }
+        }
+
+        // The version might not have been switched at all
+        let switch = Mod::get_from_db(uid).and_then(|mut modification| {
+            if modification.fingerprint() == previous_fingerprint {
+                return Ok(());
+            }
+
+            modification.switch_to_version(previous_fingerprint)
+        });
+
+        if let Err(err) = switch {
+            log::warn!(
+                "Failed to switch mod {} back to the previous version: {:?}",
+                uid,
+                err
+            );
+            return;
+        }
+
+        for installation in installations {
+            if let Err(err) = self.activate_mod_in(uid, installation).await {
+                log::warn!(
+                    "Failed to restore the previous version of mod {} in installation {}: {:?}",
+                    uid,
+                    installation.get_name(),
+                    err
+                );
+            }
+        }
+    }
+
     /// Deletes a mod from the registry and deactivates it prior to removal if necessary
     pub async fn delete_mod(&self, uid: u64) -> Result<()> {
         let modification = Mod::get_from_db(uid)?;
 
-        if modification.is_active() {
-            // Remove all active mod files injected into the game
-            modification
-                .injection_type()
-                .eject_mod(&modification)
-                .await?;
-        }
+        // Remove all active mod files injected into the game installations
+        self.eject_from_all_installations(&modification).await?;
 
         log::info!("Removing mod {} from registry", modification.name);
 
@@ -155,34 +1122,182 @@ impl ModManager {
         Ok(())
     }
 
-    /// Activates a registered mod and injects it into the game
-    pub async fn activate_mod(&self, uid: u64) -> Result<()> {
+    /// Merges a duplicate mod into another mod. The versions of the duplicate are kept as previous versions of the remaining mod, the duplicate is removed and future additions of it are treated as updates of the remaining mod.
+    pub async fn merge_mods(&self, uid: u64, duplicate_uid: u64) -> Result<()> {
+        if uid == duplicate_uid {
+            return Ok(());
+        }
+
+        let _registration = self.registration_lock.lock().await;
+
+        let mut modification = Mod::get_from_db(uid)?;
+        let duplicate = Mod::get_from_db(duplicate_uid)?;
+
+        log::info!(
+            "Merging mod {} into mod {}",
+            duplicate.name,
+            modification.name
+        );
+
+        self.eject_from_all_installations(&duplicate).await?;
+
+        // The versions are moved before the duplicate is deleted, so their archives are kept as they are used by this mod
+        modification.adopt_versions_of(&duplicate)?;
+        modification.adopt_identities_of(duplicate_uid)?;
+
+        duplicate.delete().await?;
+
+        Ok(())
+    }
+
+    /// The thumbnail of the preview image of the mod as PNG image, None if the mod has no preview image
+    pub async fn get_mod_thumbnail(&self, uid: u64) -> Result<Option<Vec<u8>>> {
+        let thumbnail = match Mod::get_from_db(uid)?.thumbnail() {
+            Some(thumbnail) => thumbnail.to_owned(),
+            None => return Ok(None),
+        };
+
+        tauri::async_runtime::spawn_blocking(move || preview::read_thumbnail(&thumbnail)).await?
+    }
+
+    /// All versions of a mod kept in the registry, ordered from oldest to newest. The last entry is the current version.
+    pub fn get_mod_versions(&self, uid: u64) -> Result<Vec<ModVersion>> {
+        Ok(Mod::get_from_db(uid)?.versions())
+    }
+
+    /// Switches the current version of a mod to the version with the provided fingerprint
+    ///
+    /// The mod is re-injected into all installations it is active in. If the new version can not be injected the previous version is restored.
+    pub async fn switch_mod_version(&self, uid: u64, fingerprint: &str) -> Result<()> {
+        let _registration = self.registration_lock.lock().await;
+
         let modification = Mod::get_from_db(uid)?;
+        let previous_fingerprint = modification.fingerprint().to_owned();
+
+        if previous_fingerprint == fingerprint {
+            return Ok(());
+        }
+
+        let installations = self.active_installations_of(&modification).await;
+
+        for (idx, installation) in installations.iter().enumerate() {
+            if let Err(err) = self.deactivate_mod_in(uid, installation).await {
+                // The version has not been switched yet, so the current version is activated again
+                self.restore_switched_version(uid, &previous_fingerprint, &installations[..idx])
+                    .await;
+
+                return Err(err);
+            }
+        }
+
+        let result = match Mod::get_from_db(uid)
+            .and_then(|mut modification| modification.switch_to_version(fingerprint))
+        {
+            Ok(()) => self.activate_mod_in_all(uid, &installations).await,
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            log::warn!(
+                "Failed to activate the new version of mod {}, restoring the previous version: {:?}",
+                modification.name,
+                err
+            );
+
+            self.restore_switched_version(uid, &previous_fingerprint, &installations)
+                .await;
 
-        if modification.is_active() {
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Switches a mod back to the version it had before the last update
+    pub async fn rollback_mod(&self, uid: u64) -> Result<()> {
+        let fingerprint = Mod::get_from_db(uid)?
+            .last_previous_version()
+            .ok_or(ModManagerError::ModVersionNotExisting)?;
+
+        self.switch_mod_version(uid, &fingerprint).await
+    }
+
+    /// Activates a registered mod and injects it into the selected game installation
+    ///
+    /// The provided choices of the option groups of the mod are selected prior to the injection.
+    pub async fn activate_mod(
+        &self,
+        uid: u64,
+        options: Option<BTreeMap<String, String>>,
+    ) -> Result<()> {
+        let installation = self.get_selected_installation().await?;
+        let mut modification = Mod::get_from_db(uid)?;
+
+        if let Some(options) = options {
+            let changed = modification.select_options(options)?;
+            let installations: Vec<String> = modification
+                .active_installations()
+                .iter()
+                .filter(|active_installation| *active_installation != installation.get_name())
+                .cloned()
+                .collect();
+
+            // The other installations would keep the files of the previous options
+            if changed && !installations.is_empty() {
+                return Err(ModManagerError::ModOptionsInUse { installations });
+            }
+        }
+
+        self.inject_into(modification, &installation).await
+    }
+
+    async fn activate_mod_in_all(
+        &self,
+        uid: u64,
+        installations: &[GameInstallation],
+    ) -> Result<()> {
+        for installation in installations {
+            self.activate_mod_in(uid, installation).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn activate_mod_in(&self, uid: u64, installation: &GameInstallation) -> Result<()> {
+        self.inject_into(Mod::get_from_db(uid)?, installation).await
+    }
+
+    /// Injects the provided mod into the installation after checking it for conflicts. The mod is only stored in the DB if the injection succeeds.
+    async fn inject_into(&self, modification: Mod, installation: &GameInstallation) -> Result<()> {
+        if modification.is_active_in(installation.get_name()) {
             return Err(ModManagerError::ModAlreadyActive);
         }
 
-        let mod_archive = ModArchive::open(APP_SAVE_PATH.join(MOD_REGISTRY_PATH).join(format!(
-            "{}.{}",
-            modification.uid, modification.archive_file_extension
-        )))
-        .await?;
+        let mod_source = modification.open_source().await?;
 
-        let (mod_file_list, mod_dir_list) = mod_archive.get_archive_dirs_and_files().await?;
+        let mapping = modification.get_file_mapping(&mod_source).await?;
+        let mod_file_list = mapping.files();
 
-        // Check if the mod conflicts with any currently activated mods
-        let conflicts = FileTreeManager::get_conflicts(&mod_file_list)?;
+        // Check if the mod conflicts with any currently activated mods in any of its target languages
+        let mut conflict_list = vec![];
 
-        if let Some(conflicts) = conflicts {
-            let mut conflict_list = vec![];
+        for language in modification.target_languages(installation) {
+            let conflicts =
+                FileTreeManager::get_conflicts(installation.get_name(), &language, &mod_file_list)?;
 
-            for (uid, conflicting_path) in conflicts {
-                let conflicting_mod = Mod::get_from_db(uid)?;
+            if let Some(conflicts) = conflicts {
+                for (uid, conflicting_path) in conflicts {
+                    let conflicting_mod = Mod::get_from_db(uid)?;
 
-                conflict_list.push((conflicting_mod.name, conflicting_path));
+                    conflict_list.push((
+                        conflicting_mod.name,
+                        format!("{}/{}", language, conflicting_path),
+                    ));
+                }
             }
+        }
 
+        if !conflict_list.is_empty() {
             return Err(ModManagerError::ModConflict {
                 conflict: conflict_list,
             });
@@ -190,27 +1305,82 @@ impl ModManager {
 
         modification
             .injection_type()
-            .inject_mod(modification, mod_archive, mod_dir_list, mod_file_list)
+            .inject_mod(modification, installation, mod_source, mapping)
             .await
     }
 
+    /// Set the game languages a mod is injected into. None resets to the game language of the installation.
+    ///
+    /// If the mod is active in the selected installation it is re-injected into the new set of languages. If the re-injection fails, the previous languages are restored.
+    pub async fn set_mod_languages(&self, uid: u64, languages: Option<Vec<String>>) -> Result<()> {
+        let languages = match languages {
+            Some(languages) if !languages.is_empty() => {
+                let mut languages = languages
+                    .iter()
+                    .map(|language| config::game_language_from_code(language))
+                    .collect::<std::result::Result<Vec<String>, ConfigError>>()?;
+
+                languages.sort();
+                languages.dedup();
+
+                Some(languages)
+            }
+            _ => None,
+        };
+
+        let installation = self.get_selected_installation().await?;
+        let mut modification = Mod::get_from_db(uid)?;
+        let was_active = modification.is_active_in(installation.get_name());
+
+        if was_active {
+            self.deactivate_mod_in(uid, &installation).await?;
+            modification = Mod::get_from_db(uid)?;
+        }
+
+        let previous_languages = modification.language_setting().map(<[String]>::to_vec);
+        modification.set_target_languages(languages)?;
+
+        if was_active {
+            if let Err(err) = self.activate_mod_in(uid, &installation).await {
+                log::warn!(
+                    "Failed to inject mod {} into its new languages, restoring the previous languages",
+                    modification.name
+                );
+
+                Mod::get_from_db(uid)?.set_target_languages(previous_languages)?;
+                self.activate_mod_in(uid, &installation).await?;
+
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Deactivates a mod in the selected game installation
     pub async fn deactivate_mod(&self, uid: u64) -> Result<()> {
+        let installation = self.get_selected_installation().await?;
+
+        self.deactivate_mod_in(uid, &installation).await
+    }
+
+    async fn deactivate_mod_in(&self, uid: u64, installation: &GameInstallation) -> Result<()> {
         let mut modification = Mod::get_from_db(uid)?;
 
-        if !modification.is_active() {
+        if !modification.is_active_in(installation.get_name()) {
             return Err(ModManagerError::ModAlreadyDeactivated);
         }
 
         modification
             .injection_type()
-            .eject_mod(&modification)
+            .eject_mod(&modification, installation)
             .await?;
 
-        modification.deactivate()
+        modification.deactivate(installation.get_name())
     }
 
-    /// Deactivates all active mods
-    pub async fn deactivate_all_mods(&self) -> Result<()> {
+    /// Deactivates all mods which are active in the provided game installation
+    pub async fn deactivate_all_mods(&self, installation: &GameInstallation) -> Result<()> {
         let tree = DB.open_tree(DB_MOD_TREE_NAME);
 
         for modification in tree.iter() {
@@ -218,13 +1388,116 @@ impl ModManager {
 
             let modification = bincode::deserialize::<Mod>(&modification)?;
 
-            if modification.is_active() {
-                self.deactivate_mod(modification.uid).await?;
+            if modification.is_active_in(installation.get_name()) {
+                self.deactivate_mod_in(modification.uid, installation)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Deactivates all mods of the installation and removes it from the config
+    pub async fn remove_installation(&self, name: &str) -> Result<()> {
+        let installation = self
+            .config
+            .read()
+            .await
+            .get_installation(name)
+            .ok_or(ConfigError::InstallationNotExisting)?
+            .clone();
+
+        // The config is only locked for writing afterwards, as deactivating the mods reads the config
+        self.deactivate_all_mods(&installation).await?;
+
+        FileTreeManager::drop_trees(installation.get_name())?;
+
+        self.config.write().await.remove_installation(name).await?;
+
+        Ok(())
+    }
+
+    /// The hashes of the files in the registry archive of the mod. If the archive can not be read no hashes are returned, which causes all files to be treated as changed.
+    async fn get_registry_file_hashes(&self, modification: &Mod) -> HashMap<String, String> {
+        // The folder of a development mod may have changed since its files were injected
+        if modification.development_path().is_some() {
+            return HashMap::new();
+        }
+
+        let hashes = match ModArchive::open(modification.archive_path()).await {
+            Ok(archive) => {
+                modification
+                    .get_file_hashes(&ModSource::Archive(archive))
+                    .await
             }
+            Err(err) => Err(err),
+        };
+
+        hashes.unwrap_or_else(|err| {
+            log::warn!(
+                "Failed to read registry archive of mod {}: {:?}",
+                modification.name,
+                err
+            );
+            HashMap::new()
+        })
+    }
+
+    /// The configured game installations the mod is active in
+    async fn active_installations_of(&self, modification: &Mod) -> Vec<GameInstallation> {
+        let config = self.config.read().await;
+
+        modification
+            .active_installations()
+            .iter()
+            .filter_map(|name| config.get_installation(name).cloned())
+            .collect()
+    }
+
+    /// Removes the mod files from every game installation the mod is active in
+    async fn eject_from_all_installations(&self, modification: &Mod) -> Result<()> {
+        let config = self.config.read().await;
+
+        for installation_name in modification.active_installations() {
+            let installation = match config.get_installation(installation_name) {
+                Some(installation) => installation,
+                None => {
+                    log::warn!(
+                        "Mod {} is active in unknown installation '{}', skipping ejection",
+                        modification.name,
+                        installation_name
+                    );
+                    continue;
+                }
+            };
+
+            modification
+                .injection_type()
+                .eject_mod(modification, installation)
+                .await?;
         }
 
         Ok(())
     }
+
+    /// The game installation all mod operations are applied to
+    async fn get_selected_installation(&self) -> Result<GameInstallation> {
+        let config = self.config.read().await;
+
+        config
+            .get_selected_installation()
+            .cloned()
+            .ok_or(ModManagerError::AppNotInitialized)
+    }
+
+    /// The localization folder of the language of the selected installation, None if no installation is configured
+    async fn get_localization_path(&self) -> Option<PathBuf> {
+        self.config
+            .read()
+            .await
+            .get_selected_installation()
+            .map(|installation| installation.get_localization_path(installation.get_language()))
+    }
 }
 
 /// Event which is sent to the Frontend if any Mod in the database changes or is deleted
@@ -0,0 +1,87 @@
+//! Optional components of mods, like alternative crosshair colors, of which the user selects one on activation
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{ModManagerError, Result};
+
+/// A group of alternatives of a mod declared in the modinfo.json. Exactly one choice of every group is injected into the game.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OptionGroup {
+    pub name: String,
+    /// The alternatives of the group, the first one is selected by default
+    pub choices: Vec<OptionChoice>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OptionChoice {
+    pub name: String,
+    /// Folder inside the mod containing the files of the choice. Its content is injected as if it was placed in the base of the mod.
+    pub folder: String,
+}
+
+impl OptionGroup {
+    /// The selected choice of the group. Falls back to the first choice if no existing choice has been selected.
+    pub fn selected_choice(&self, selection: &BTreeMap<String, String>) -> Option<&OptionChoice> {
+        selection
+            .get(&self.name)
+            .and_then(|selected| self.choice(selected))
+            .or_else(|| self.choices.first())
+    }
+
+    fn choice(&self, name: &str) -> Option<&OptionChoice> {
+        self.choices.iter().find(|choice| choice.name == name)
+    }
+}
+
+impl OptionChoice {
+    /// The folder of the choice relative to the path prefix, including the trailing slash
+    pub fn folder_prefix(&self) -> String {
+        format!("{}/", self.folder.replace('\\', "/").trim_matches('/'))
+    }
+}
+
+/// Checks that the selection only contains existing groups and choices
+pub fn validate_selection(
+    groups: &[OptionGroup],
+    selection: &BTreeMap<String, String>,
+) -> Result<()> {
+    for (group, choice) in selection {
+        let exists = groups
+            .iter()
+            .find(|option_group| &option_group.name == group)
+            .and_then(|option_group| option_group.choice(choice))
+            .is_some();
+
+        if !exists {
+            return Err(ModManagerError::ModOptionNotExisting {
+                group: group.to_owned(),
+                choice: choice.to_owned(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether both selections result in the same choice of every group
+pub fn same_choices(
+    groups: &[OptionGroup],
+    selection: &BTreeMap<String, String>,
+    other: &BTreeMap<String, String>,
+) -> bool {
+    groups
+        .iter()
+        .all(|group| group.selected_choice(selection) == group.selected_choice(other))
+}
+
+/// Keeps the selected choices which still exist in the provided groups
+pub fn retain_existing(groups: &[OptionGroup], selection: &mut BTreeMap<String, String>) {
+    selection.retain(|group, choice| {
+        groups
+            .iter()
+            .find(|option_group| &option_group.name == group)
+            .and_then(|option_group| option_group.choice(choice))
+            .is_some()
+    });
+}
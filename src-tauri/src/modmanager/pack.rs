@@ -0,0 +1,108 @@
+//! Detection of mod packs, archives which bundle several mods
+//!
+//! A mod pack either contains a `modpack.json` manifest listing the folders of its mods, or multiple folders which each contain their own `modinfo.json` file.
+//! Mod packs are split on import, so every contained mod is managed on its own.
+use serde::{Deserialize, Serialize};
+
+use super::error::{ModManagerError, Result};
+
+/// Name of the manifest file which lists the mod folders of a mod pack
+pub const PACK_MANIFEST_FILE: &str = "modpack.json";
+
+/// Manifest of a mod pack
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackManifest {
+    /// Folders of the contained mods, relative to the manifest
+    pub mods: Vec<String>,
+}
+
+impl PackManifest {
+    /// Parses the content of a modpack.json file
+    pub fn parse(file: &[u8]) -> Result<Self> {
+        serde_json::from_slice(file).map_err(|err| ModManagerError::InvalidPackManifest {
+            msg: err.to_string(),
+        })
+    }
+
+    /// Resolves the mod folders of the manifest to the entries of the archive. Returns the folders including the trailing slash.
+    pub fn mod_folders(&self, entries: &[String], manifest_path: &str) -> Result<Vec<String>> {
+        // The folders are relative to the folder of the manifest
+        let base = match manifest_path.rfind('/') {
+            Some(index) => &manifest_path[..=index],
+            None => "",
+        };
+
+        let mut folders = vec![];
+
+        for folder in &self.mods {
+            let folder = folder.replace('\\', "/");
+            let folder = folder.trim_matches('/');
+
+            if folder.is_empty() || folder.split('/').any(|component| component == "..") {
+                return Err(ModManagerError::InvalidPackManifest {
+                    msg: format!("'{}' is not a valid mod folder", folder),
+                });
+            }
+
+            let folder = format!("{}{}/", base, folder);
+
+            if !entries.iter().any(|entry| entry.starts_with(&folder)) {
+                return Err(ModManagerError::InvalidPackManifest {
+                    msg: format!("The mod folder '{}' does not exist in the archive", folder),
+                });
+            }
+
+            folders.push(folder);
+        }
+
+        if folders.is_empty() {
+            return Err(ModManagerError::InvalidPackManifest {
+                msg: String::from("The manifest does not list any mods"),
+            });
+        }
+
+        Ok(folders)
+    }
+}
+
+/// Detects the mod folders of a mod pack without manifest, which are all folders containing a modinfo.json file. Returns the folders including the trailing slash.
+///
+/// Returns None if the entries contain a single mod, which is the case if there is a modinfo.json file at the base or less than two mod folders.
+pub fn detect_mod_folders(entries: &[String]) -> Option<Vec<String>> {
+    let mut folders: Vec<&str> = entries
+        .iter()
+        .filter_map(|entry| {
+            let file_name = entry.rsplit('/').next().unwrap_or(entry);
+
+            if file_name.eq_ignore_ascii_case("modinfo.json") {
+                Some(&entry[..entry.len() - file_name.len()])
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if folders.contains(&"") {
+        return None;
+    }
+
+    folders.sort_unstable();
+
+    // A modinfo.json file nested inside of another mod folder belongs to the files of that mod
+    let mut mod_folders: Vec<String> = vec![];
+
+    for folder in folders {
+        if !mod_folders
+            .iter()
+            .any(|mod_folder| folder.starts_with(mod_folder.as_str()))
+        {
+            mod_folders.push(folder.to_owned());
+        }
+    }
+
+    if mod_folders.len() < 2 {
+        return None;
+    }
+
+    Some(mod_folders)
+}
@@ -0,0 +1,93 @@
+//! Preview images of mods, which are shown as thumbnail in the mod list
+//!
+//! The preview image is either referenced by the `preview` field of the modinfo.json or found by its conventional name in the base of the mod.
+//! Like the modinfo.json file it is not injected into the game.
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use image::ImageFormat;
+use sha2::{Digest, Sha256};
+
+use crate::config::APP_SAVE_PATH;
+
+use super::error::Result;
+
+/// Folder of the thumbnail cache inside the [`APP_SAVE_PATH`]
+pub const THUMBNAIL_PATH: &str = "thumbnails";
+
+/// Conventional names of preview images in the base of a mod, ordered by precedence
+pub(super) const PREVIEW_FILE_NAMES: [&str; 3] = ["preview.png", "preview.jpg", "preview.jpeg"];
+
+/// Maximum width and height of a thumbnail in pixels. The aspect ratio of the preview image is kept.
+const THUMBNAIL_SIZE: u32 = 256;
+
+/// Finds the preview image among the files of a mod. Returns the path of the file as it is listed in the files.
+///
+/// The preview declared in the modinfo.json takes precedence over the conventional names.
+pub fn find_preview(files: &[String], declared: Option<&str>) -> Option<String> {
+    if let Some(declared) = declared {
+        let declared = declared.replace('\\', "/");
+        let declared = declared.trim_start_matches("./");
+
+        match files
+            .iter()
+            .find(|file| file.eq_ignore_ascii_case(declared))
+        {
+            Some(file) => return Some(file.clone()),
+            None => log::warn!(
+                "The preview image '{}' declared in the modinfo.json does not exist",
+                declared
+            ),
+        }
+    }
+
+    PREVIEW_FILE_NAMES.iter().find_map(|name| {
+        files
+            .iter()
+            .find(|file| file.eq_ignore_ascii_case(name))
+            .cloned()
+    })
+}
+
+/// Downscales the preview image into the thumbnail cache. Returns the file name of the thumbnail.
+///
+/// The name contains the hash of the image, so every version of a mod with a different preview gets its own thumbnail. This is blocking and has to be called inside of a blocking task.
+pub fn create_thumbnail(uid: u64, image: &[u8]) -> Result<String> {
+    let name = format!("{}-{:x}.png", uid, Sha256::digest(image));
+    let path = thumbnail_path(&name);
+
+    if path.is_file() {
+        return Ok(name);
+    }
+
+    let thumbnail = image::load_from_memory(image)?.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+
+    fs::create_dir_all(APP_SAVE_PATH.join(THUMBNAIL_PATH))?;
+    thumbnail.save_with_format(path, ImageFormat::Png)?;
+
+    Ok(name)
+}
+
+/// Reads the thumbnail with the provided file name from the cache. Returns None if the thumbnail has been removed from the cache.
+pub fn read_thumbnail(name: &str) -> Result<Option<Vec<u8>>> {
+    match fs::read(thumbnail_path(name)) {
+        Ok(thumbnail) => Ok(Some(thumbnail)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Deletes the thumbnail with the provided file name from the cache
+pub fn delete_thumbnail(name: &str) -> Result<()> {
+    match fs::remove_file(thumbnail_path(name)) {
+        Ok(_) => Ok(()),
+        // Ignore not found error as we are deleting the thumbnail anyways
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn thumbnail_path(name: &str) -> PathBuf {
+    APP_SAVE_PATH.join(THUMBNAIL_PATH).join(name)
+}
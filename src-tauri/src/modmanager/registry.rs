@@ -1,52 +1,233 @@
 //! The mod registry
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
-use crate::config::APP_SAVE_PATH;
+use crate::config::{self, GameInstallation, DATA_PATH};
 use crate::db::{BincodeDb, Key};
 use crate::DB;
 
-use super::archive::{ModArchive, ModInfo};
+use super::archive::{self, ArchiveFormat, ModArchive, ModInfo, MODINFO_SCHEMA_VERSION};
+use super::directory::ModDirectory;
 use super::error::{ModManagerError, Result};
 use super::injection::InjectionType;
+use super::localized::{self, LocalizedText};
+use super::mapping::{FileMapping, FileRule};
+use super::options::{self, OptionGroup};
+use super::preview;
+use super::source::ModSource;
 
 pub const DB_MOD_TREE_NAME: &str = "modtree";
+/// Index of the [`ModIdentity`] keys of all mods, pointing to the uid of the mod
+pub const DB_MOD_ID_TREE_NAME: &str = "modidtree";
 pub const MOD_REGISTRY_PATH: &str = "registry";
 
 /// A single AW mod
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Mod {
+    /// Name of the mod. For translated mods this is the English or first translation, unless the mod has been localized with [`Mod::localize()`]
     pub name: String,
     /// Unique identifier for the mod, used for internal functions
     pub uid: u64,
-    /// As multiple archive file extensions have to be supported the file extension of each mod is stored
-    pub archive_file_extension: String,
+    /// Stable identifier declared by the mod author in the modinfo.json, e.g. `author.modname`
+    id: Option<String>,
+    /// SHA-256 hash of the mod archive. The archive is stored in the registry under this hash and verified against it before activation.
+    fingerprint: String,
+    /// Format of the mod archive, detected from its content
+    archive_format: ArchiveFormat,
+    /// Prefix which is stripped from all archive paths on injection to fix a misplaced archive layout
+    path_prefix: Option<String>,
+    /// Folder of a development mod. Its files are read directly from the folder instead of a registry archive.
+    development_path: Option<PathBuf>,
     author: Option<String>,
     pub version: Option<Version>,
     info: Option<String>,
     /// Type of mod injection that is required to install this mod
     injection: InjectionType,
-    /// Whether the mod is currently active and installed in the game or not
-    is_active: bool,
+    /// Names of the game installations the mod is currently active and installed in
+    active_installations: Vec<String>,
+    /// Game languages the mod is injected into as declared by its modinfo.json. If None the mod is injected into the game language of the installation
+    declared_languages: Option<Vec<String>>,
+    /// Game languages selected by the user, which take precedence over the declared languages
+    target_languages: Option<Vec<String>>,
+    /// Versions of the mod which have been replaced by newer ones, ordered from oldest to newest
+    previous_versions: Vec<ModVersion>,
+    metadata: ModMetadata,
+    translations: ModTranslations,
+    /// Path of the preview image inside the path prefix. It is not injected into the game.
+    preview: Option<String>,
+    /// File name of the thumbnail of the preview image in the thumbnail cache
+    thumbnail: Option<String>,
+    /// Groups of alternatives of the mod, of which one choice each is injected
+    option_groups: Vec<OptionGroup>,
+    /// The choices selected by the user keyed by the name of their option group. Groups without selection use their first choice.
+    selected_options: BTreeMap<String, String>,
+    /// Rules placing files of the mod into other folders of the game
+    file_rules: Vec<FileRule>,
+}
+
+/// Translations of the name and info of a mod keyed by language code. Empty if the modinfo.json does not translate them.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ModTranslations {
+    names: BTreeMap<String, String>,
+    infos: BTreeMap<String, String>,
+}
+
+/// Optional metadata of a mod declared in the extended fields of the modinfo.json
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ModMetadata {
+    /// Short summary of the mod, while the info contains the full description
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub license: Option<String>,
+    pub tags: Vec<String>,
+    /// Oldest modloader version the mod works with
+    pub min_modloader_version: Option<Version>,
+    /// Game languages the mod has been made for. None if the mod does not declare them.
+    pub supported_languages: Option<Vec<String>>,
+}
+
+/// A version of a mod kept in the registry, containing all version specific data of a [`Mod`]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModVersion {
+    /// SHA-256 hash of the archive of the version, identifies the version and its archive in the registry
+    fingerprint: String,
+    name: String,
+    id: Option<String>,
+    archive_format: ArchiveFormat,
+    path_prefix: Option<String>,
+    author: Option<String>,
+    version: Option<Version>,
+    info: Option<String>,
+    injection: InjectionType,
+    declared_languages: Option<Vec<String>>,
+    metadata: ModMetadata,
+    translations: ModTranslations,
+    preview: Option<String>,
+    thumbnail: Option<String>,
+    option_groups: Vec<OptionGroup>,
+    file_rules: Vec<FileRule>,
+}
+
+impl ModVersion {
+    /// Path of the archive file of the version in the registry
+    fn archive_path(&self) -> PathBuf {
+        archive_path(&self.fingerprint, self.archive_format.extension())
+    }
+
+    /// Delete the archive file of the version from the registry
+    fn delete_archive(&self) -> Result<()> {
+        match fs::remove_file(self.archive_path()) {
+            Ok(_) => Ok(()),
+            // Ignore not found error as we are deleting the archive anyways
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Delete the thumbnail of the version from the thumbnail cache
+    fn delete_thumbnail(&self) -> Result<()> {
+        match &self.thumbnail {
+            Some(thumbnail) => preview::delete_thumbnail(thumbnail),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Mod {
     /// Create a [`Mod`] from a [`ModInfo`] struct
-    pub fn from_mod_info(mod_info: ModInfo, archive_file_extension: &str) -> Result<Self> {
+    pub fn from_mod_info(
+        mod_info: ModInfo,
+        archive_format: ArchiveFormat,
+        fingerprint: &str,
+        path_prefix: Option<String>,
+    ) -> Result<Self> {
+        let id = match mod_info.id {
+            Some(id) if id.trim().is_empty() => {
+                return Err(ModManagerError::InvalidModInfo {
+                    msg: String::from("The mod id must not be empty"),
+                })
+            }
+            Some(id) => Some(id.trim().to_lowercase()),
+            None => None,
+        };
+
+        let declared_languages = mod_info
+            .languages
+            .as_deref()
+            .map(game_languages)
+            .transpose()?;
+
+        let min_modloader_version = match mod_info.min_modloader_version {
+            Some(version) => {
+                let version = Version::parse(&version)
+                    .map_err(|e| ModManagerError::InvalidModInfo { msg: e.to_string() })?;
+
+                let modloader_version = Version::parse(env!("CARGO_PKG_VERSION"))
+                    .expect("The modloader version is not a valid semver version");
+
+                if version > modloader_version {
+                    return Err(ModManagerError::ModloaderOutdated {
+                        required: version.to_string(),
+                    });
+                }
+
+                Some(version)
+            }
+            None => None,
+        };
+
+        let metadata = ModMetadata {
+            description: mod_info.description,
+            homepage: mod_info.homepage,
+            license: mod_info.license,
+            tags: mod_info.tags.unwrap_or_default(),
+            min_modloader_version,
+            supported_languages: mod_info
+                .supported_languages
+                .as_deref()
+                .map(game_languages)
+                .transpose()?,
+        };
+
+        let translations = ModTranslations {
+            names: mod_info.name.translations(),
+            infos: mod_info.info.translations(),
+        };
+
+        let uid = DB.get_inner().generate_id()?;
+
         Ok(Self {
-            name: mod_info.name,
-            uid: DB.get_inner().generate_id()?,
-            archive_file_extension: archive_file_extension.to_owned(),
+            name: mod_info.name.get(None).to_owned(),
+            uid,
+            id,
+            fingerprint: fingerprint.to_owned(),
+            archive_format,
+            path_prefix,
+            development_path: None,
             author: Some(mod_info.author),
             version: Some(
                 Version::parse(&mod_info.version)
                     .map_err(|e| ModManagerError::InvalidModInfo { msg: e.to_string() })?,
             ),
-            info: Some(mod_info.info),
+            info: Some(mod_info.info.get(None).to_owned()),
             injection: mod_info.injection,
-            is_active: false,
+            active_installations: vec![],
+            declared_languages,
+            target_languages: None,
+            previous_versions: vec![],
+            metadata,
+            translations,
+            preview: None,
+            thumbnail: None,
+            option_groups: mod_info.options.unwrap_or_default(),
+            selected_options: BTreeMap::new(),
+            file_rules: mod_info.files.unwrap_or_default(),
         })
     }
 
@@ -56,17 +237,35 @@ impl Mod {
     pub fn new(
         name: &str,
         injection_type: InjectionType,
-        archive_file_extension: &str,
+        archive_format: ArchiveFormat,
+        fingerprint: &str,
+        path_prefix: Option<String>,
     ) -> Result<Self> {
+        let uid = DB.get_inner().generate_id()?;
+
         Ok(Self {
             name: name.to_owned(),
-            uid: DB.get_inner().generate_id()?,
-            archive_file_extension: archive_file_extension.to_owned(),
+            uid,
+            id: None,
+            fingerprint: fingerprint.to_owned(),
+            archive_format,
+            path_prefix,
+            development_path: None,
             author: None,
             version: None,
             info: None,
             injection: injection_type,
-            is_active: false,
+            active_installations: vec![],
+            declared_languages: None,
+            target_languages: None,
+            previous_versions: vec![],
+            metadata: ModMetadata::default(),
+            translations: ModTranslations::default(),
+            preview: None,
+            thumbnail: None,
+            option_groups: vec![],
+            selected_options: BTreeMap::new(),
+            file_rules: vec![],
         })
     }
 
@@ -77,28 +276,425 @@ impl Mod {
             .ok_or(ModManagerError::ModNotExisting)
     }
 
-    // If the mod is currently active in the game
-    pub fn is_active(&self) -> bool {
-        self.is_active
+    /// Names of all game installations at least one mod is active in
+    pub fn installations_with_active_mods() -> Result<HashSet<String>> {
+        let tree = DB.open_tree(DB_MOD_TREE_NAME);
+
+        let mut installations = HashSet::new();
+
+        for entry in tree.iter() {
+            let (_, value) = entry?;
+            let modification = bincode::deserialize::<Mod>(&value)?;
+
+            installations.extend(modification.active_installations);
+        }
+
+        Ok(installations)
+    }
+
+    /// If the mod is currently active in the provided game installation
+    pub fn is_active_in(&self, installation: &str) -> bool {
+        self.active_installations
+            .iter()
+            .any(|active_installation| active_installation == installation)
+    }
+
+    /// All game installations the mod is currently active in
+    pub fn active_installations(&self) -> &Vec<String> {
+        &self.active_installations
+    }
+
+    /// The game languages the mod is injected into in the provided installation
+    ///
+    /// The languages selected by the user take precedence over the languages declared by the mod.
+    pub fn target_languages(&self, installation: &GameInstallation) -> Vec<String> {
+        match self
+            .target_languages
+            .as_ref()
+            .or(self.declared_languages.as_ref())
+        {
+            Some(languages) => languages.clone(),
+            None => vec![installation.get_language().to_owned()],
+        }
+    }
+
+    /// Keeps the settings of the user made for an older version of the mod
+    ///
+    /// The selected target languages are kept. Selected options are kept if the choice still exists in this version.
+    pub fn inherit_user_settings(&mut self, other: &Self) {
+        self.target_languages = other.target_languages.clone();
+
+        self.selected_options = other.selected_options.clone();
+        options::retain_existing(&self.option_groups, &mut self.selected_options);
+    }
+
+    /// The game languages selected by the user, None if the mod is injected into its declared languages or the game language of the installation
+    pub fn language_setting(&self) -> Option<&[String]> {
+        self.target_languages.as_deref()
+    }
+
+    /// Set the game languages the mod is injected into. None resets to the declared languages of the mod or the game language of the installation.
+    pub fn set_target_languages(&mut self, languages: Option<Vec<String>>) -> Result<()> {
+        let tree = DB.open_tree(DB_MOD_TREE_NAME);
+
+        self.target_languages = languages;
+
+        tree.b_insert(&Key::<Mod>::new(&self.uid.to_string()), self)?;
+
+        Ok(())
+    }
+
+    /// Select the choices of the option groups which are injected, keyed by the name of their group. Groups which are not part of the selection keep their selected choice.
+    ///
+    /// The selection is not stored in the DB, it is stored together with the mod once the mod has been injected with it. Returns whether the injected files change.
+    pub fn select_options(&mut self, selection: BTreeMap<String, String>) -> Result<bool> {
+        options::validate_selection(&self.option_groups, &selection)?;
+
+        let previous_selection = self.selected_options.clone();
+        self.selected_options.extend(selection);
+
+        Ok(!options::same_choices(
+            &self.option_groups,
+            &previous_selection,
+            &self.selected_options,
+        ))
+    }
+
+    /// Translate the name and info into the provided language code. They are kept if the mod is not translated into the language.
+    ///
+    /// The localized mod is meant to be shown to the user and must not be stored in the DB, as it would replace the default name and info.
+    pub fn localize(&mut self, language: Option<&str>) {
+        if let Some((_, name)) = localized::select(&self.translations.names, language) {
+            self.name = name.clone();
+        }
+
+        if let Some((_, info)) = localized::select(&self.translations.infos, language) {
+            self.info = Some(info.clone());
+        }
+    }
+
+    /// The modinfo.json describing the current version. None if the mod has been added without modinfo.json.
+    pub fn to_mod_info(&self, changelog: Option<HashMap<String, String>>) -> Option<ModInfo> {
+        let version = self.version.as_ref()?;
+        let metadata = self.metadata.clone();
+
+        Some(ModInfo {
+            schema_version: Some(MODINFO_SCHEMA_VERSION),
+            id: self.id.clone(),
+            name: translated(self.name.clone(), &self.translations.names),
+            author: self.author.clone().unwrap_or_default(),
+            version: version.to_string(),
+            info: translated(
+                self.info.clone().unwrap_or_default(),
+                &self.translations.infos,
+            ),
+            injection: self.injection.clone(),
+            languages: self.declared_languages.as_deref().map(language_codes),
+            changelog,
+            description: metadata.description,
+            homepage: metadata.homepage,
+            license: metadata.license,
+            tags: Some(metadata.tags).filter(|tags| !tags.is_empty()),
+            min_modloader_version: metadata
+                .min_modloader_version
+                .map(|version| version.to_string()),
+            supported_languages: metadata.supported_languages.as_deref().map(language_codes),
+            preview: self.preview.clone(),
+            options: Some(self.option_groups.clone()).filter(|groups| !groups.is_empty()),
+            files: Some(self.file_rules.clone()).filter(|rules| !rules.is_empty()),
+        })
+    }
+
+    /// Set the path of the preview image inside the path prefix
+    pub fn set_preview(&mut self, preview: Option<String>) {
+        self.preview = preview;
+    }
+
+    /// File name of the thumbnail of the preview image in the thumbnail cache, None if the mod has no preview image
+    pub fn thumbnail(&self) -> Option<&str> {
+        self.thumbnail.as_deref()
+    }
+
+    /// Thumbnail of the current version which no previous version uses, so it can be deleted if the current version is discarded
+    pub fn unshared_thumbnail(&self) -> Option<String> {
+        self.thumbnail.clone().filter(|thumbnail| {
+            !self
+                .previous_versions
+                .iter()
+                .any(|version| version.thumbnail.as_ref() == Some(thumbnail))
+        })
+    }
+
+    /// Downscales the preview image of the source into the thumbnail cache
+    ///
+    /// A broken preview image must not prevent adding the mod, so the mod is kept without thumbnail in that case.
+    pub async fn create_thumbnail(&mut self, mod_source: &ModSource) {
+        let preview = match &self.preview {
+            Some(preview) => format!("{}{}", self.path_prefix(), preview),
+            None => return,
+        };
+
+        let uid = self.uid;
+        let mod_source = mod_source.clone();
+        let thumbnail = tauri::async_runtime::spawn_blocking(move || {
+            let image = mod_source.reader()?.read(&preview)?;
+
+            preview::create_thumbnail(uid, &image)
+        })
+        .await
+        .map_err(ModManagerError::from)
+        .and_then(|thumbnail| thumbnail);
+
+        self.thumbnail = match thumbnail {
+            Ok(thumbnail) => Some(thumbnail),
+            Err(err) => {
+                log::warn!(
+                    "Failed to create the thumbnail of mod {}: {:?}",
+                    self.name,
+                    err
+                );
+                None
+            }
+        };
+    }
+
+    /// Maps the files of the source which are injected into the game to their paths inside the game
+    ///
+    /// Only the files of the selected option choices are injected, placed according to the file rules. The preview image is skipped, just like the modinfo.json file.
+    pub async fn get_file_mapping(&self, mod_source: &ModSource) -> Result<FileMapping> {
+        let (files, _) = mod_source.get_dirs_and_files(self.path_prefix()).await?;
+
+        Ok(FileMapping::new(
+            &files,
+            self.preview.as_deref(),
+            &self.option_groups,
+            &self.selected_options,
+            &self.file_rules,
+        ))
+    }
+
+    /// Calculates the SHA-256 hash of every file of the mod which is injected into the game, keyed by the path inside the game
+    pub async fn get_file_hashes(&self, mod_source: &ModSource) -> Result<HashMap<String, String>> {
+        let mapping = self.get_file_mapping(mod_source).await?;
+        let hashes = mod_source.get_file_hashes(self.path_prefix()).await?;
+
+        Ok(mapping.map_hashes(&hashes))
+    }
+
+    /// SHA-256 hash of the archive of the current version
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Prefix of the archive paths which is stripped on injection, empty if the archive layout is correct
+    pub fn path_prefix(&self) -> &str {
+        self.path_prefix.as_deref().unwrap_or_default()
+    }
+
+    /// Path of the archive file of the current version in the registry
+    pub fn archive_path(&self) -> PathBuf {
+        archive_path(&self.fingerprint, self.archive_format.extension())
+    }
+
+    /// Folder the files of a development mod are read from, None for mods stored in the registry
+    pub fn development_path(&self) -> Option<&Path> {
+        self.development_path.as_deref()
+    }
+
+    /// Turn the mod into a development mod whose files are read from the provided folder
+    pub fn set_development_path(&mut self, path: PathBuf) {
+        self.development_path = Some(path);
+    }
+
+    /// Opens the source the files of the current version are read from. Registry archives are verified before they are opened.
+    pub async fn open_source(&self) -> Result<ModSource> {
+        if let Some(path) = &self.development_path {
+            return Ok(ModSource::Directory(ModDirectory::open(path).await?));
+        }
+
+        // Report damaged registry archives before they cause obscure extraction errors
+        self.verify_archive().await?;
+
+        Ok(ModSource::Archive(
+            ModArchive::open(self.archive_path()).await?,
+        ))
+    }
+
+    /// Checks that the archive of the current version in the registry still matches its hash
+    pub async fn verify_archive(&self) -> Result<()> {
+        let archive_path = self.archive_path();
+        let fingerprint = self.fingerprint.clone();
+        let name = self.name.clone();
+
+        tauri::async_runtime::spawn_blocking(move || {
+            if !archive_path.is_file() || archive::hash_file(&archive_path)? != fingerprint {
+                log::error!(
+                    "Registry archive {:?} does not match its hash",
+                    archive_path
+                );
+
+                return Err(ModManagerError::ArchiveCorrupted { name });
+            }
+
+            Ok(())
+        })
+        .await?
+    }
+
+    /// All versions of the mod kept in the registry, ordered from oldest to newest. The last entry is the current version.
+    pub fn versions(&self) -> Vec<ModVersion> {
+        let mut versions = self.previous_versions.clone();
+        versions.push(self.current_version());
+
+        versions
+    }
+
+    /// Fingerprint of the most recent previous version, if any
+    pub fn last_previous_version(&self) -> Option<String> {
+        self.previous_versions
+            .last()
+            .map(|version| version.fingerprint.clone())
+    }
+
+    fn current_version(&self) -> ModVersion {
+        ModVersion {
+            fingerprint: self.fingerprint.clone(),
+            name: self.name.clone(),
+            id: self.id.clone(),
+            archive_format: self.archive_format,
+            path_prefix: self.path_prefix.clone(),
+            author: self.author.clone(),
+            version: self.version.clone(),
+            info: self.info.clone(),
+            injection: self.injection.clone(),
+            declared_languages: self.declared_languages.clone(),
+            metadata: self.metadata.clone(),
+            translations: self.translations.clone(),
+            preview: self.preview.clone(),
+            thumbnail: self.thumbnail.clone(),
+            option_groups: self.option_groups.clone(),
+            file_rules: self.file_rules.clone(),
+        }
+    }
+
+    /// Previous versions are always registry archives, so applying one ends the development of the mod
+    fn apply_version(&mut self, version: ModVersion) {
+        self.development_path = None;
+        self.name = version.name;
+        self.id = version.id;
+        self.fingerprint = version.fingerprint;
+        self.archive_format = version.archive_format;
+        self.path_prefix = version.path_prefix;
+        self.author = version.author;
+        self.version = version.version;
+        self.info = version.info;
+        self.injection = version.injection;
+        self.declared_languages = version.declared_languages;
+        self.metadata = version.metadata;
+        self.translations = version.translations;
+        self.preview = version.preview;
+        self.thumbnail = version.thumbnail;
+        self.option_groups = version.option_groups;
+        self.file_rules = version.file_rules;
+        options::retain_existing(&self.option_groups, &mut self.selected_options);
+    }
+
+    /// Keeps all versions of the provided older mod entry as previous versions of this mod. A previous version with the same archive as this version is replaced by this version, as both share the same registry archive.
+    ///
+    /// Versions without a registry archive, like development mods, can not be restored and are dropped.
+    pub fn keep_previous_versions(&mut self, other: &Self) {
+        let mut previous_versions = other.versions();
+
+        previous_versions.retain(|version| {
+            !version.fingerprint.is_empty() && version.fingerprint != self.fingerprint
+        });
+        self.previous_versions = previous_versions;
+    }
+
+    /// Keeps all versions of the provided duplicate mod as previous versions of this mod, ordered before the own previous versions. Versions sharing their archive with a version of this mod are skipped.
+    pub fn adopt_versions_of(&mut self, duplicate: &Self) -> Result<()> {
+        let tree = DB.open_tree(DB_MOD_TREE_NAME);
+
+        let known_fingerprints: HashSet<String> = self
+            .versions()
+            .into_iter()
+            .map(|version| version.fingerprint)
+            .collect();
+
+        let mut previous_versions: Vec<ModVersion> = duplicate
+            .versions()
+            .into_iter()
+            .filter(|version| {
+                !version.fingerprint.is_empty()
+                    && !known_fingerprints.contains(&version.fingerprint)
+            })
+            .collect();
+        previous_versions.append(&mut self.previous_versions);
+        self.previous_versions = previous_versions;
+
+        tree.b_insert(&Key::<Mod>::new(&self.uid.to_string()), self)?;
+
+        Ok(())
+    }
+
+    /// Make the previous version with the provided fingerprint the current version of the mod. The current version is kept as previous version.
+    ///
+    /// # Caution
+    /// The mod has to be deactivated in all installations prior to switching the version.
+    pub fn switch_to_version(&mut self, fingerprint: &str) -> Result<()> {
+        let tree = DB.open_tree(DB_MOD_TREE_NAME);
+
+        let idx = self
+            .previous_versions
+            .iter()
+            .position(|version| version.fingerprint == fingerprint)
+            .ok_or(ModManagerError::ModVersionNotExisting)?;
+
+        let version = self.previous_versions.remove(idx);
+
+        if !self.fingerprint.is_empty() {
+            self.previous_versions.push(self.current_version());
+        }
+
+        self.apply_version(version);
+
+        tree.b_insert(&Key::<Mod>::new(&self.uid.to_string()), self)?;
+        self.index_identities()
     }
 
     pub fn injection_type(&self) -> InjectionType {
         self.injection.clone()
     }
 
-    // If the mod already exists in the registry the Option contains the existing entry
-    pub async fn is_already_existing(&self) -> Result<Option<Self>> {
-        let mod_name = self.name.clone();
-        tauri::async_runtime::spawn_blocking(move || {
-            let tree = DB.open_tree(DB_MOD_TREE_NAME);
+    /// The identities used to detect whether the mod is already registered, ordered by precedence
+    pub fn identities(&self) -> Vec<ModIdentity> {
+        let mut identities = vec![];
+
+        if let Some(id) = &self.id {
+            identities.push(ModIdentity::Id(id.clone()));
+        }
+
+        // Mods migrated without archive and development mods have no fingerprint which could identify them
+        if !self.fingerprint.is_empty() {
+            identities.push(ModIdentity::Fingerprint(self.fingerprint.clone()));
+        }
+
+        if let Some(path) = &self.development_path {
+            identities.push(ModIdentity::Directory(path.clone()));
+        }
 
-            for existing_modification in tree.iter() {
-                let (_, value) = existing_modification?;
+        identities
+    }
 
-                let existing_modification = bincode::deserialize::<Mod>(&value)?;
+    // If the mod already exists in the registry the Option contains the existing entry. An identical archive is detected by its fingerprint even if it has already been registered under another id.
+    pub async fn is_already_existing(&self) -> Result<Option<Self>> {
+        let identities = self.identities();
+        tauri::async_runtime::spawn_blocking(move || {
+            let id_tree = DB.open_tree(DB_MOD_ID_TREE_NAME);
 
-                if existing_modification.name.eq(&mod_name) {
-                    return Ok(Some(existing_modification));
+            for identity in identities {
+                if let Some(uid) = id_tree.b_get(&identity.key())? {
+                    return Self::get_from_db(uid).map(Some);
                 }
             }
 
@@ -107,91 +703,371 @@ impl Mod {
         .await?
     }
 
-    /// If the mod version of the current mod is newer than other
-    ///
-    /// If no version information is available the function assumes that self is newer
-    pub fn is_newer_version(&self, other: &Self) -> bool {
-        if other.version.is_some() && self.version.is_some() {
-            return other.version.as_ref().unwrap() < self.version.as_ref().unwrap();
+    /// The kind of change replacing the other mod with the current mod would be
+    pub fn version_change(&self, other: &Self) -> VersionChange {
+        let (new_version, installed_version) = match (&self.version, &other.version) {
+            (Some(new_version), Some(installed_version)) => (new_version, installed_version),
+            _ => return VersionChange::Unknown,
+        };
+
+        if new_version.pre.is_empty() != installed_version.pre.is_empty() {
+            return VersionChange::PrereleaseSwitch;
         }
 
-        true
+        match new_version.cmp(installed_version) {
+            Ordering::Greater => VersionChange::Upgrade,
+            Ordering::Equal => VersionChange::Reinstall,
+            Ordering::Less => VersionChange::Downgrade,
+        }
     }
 
-    /// Insert the mod into the database
+    /// Insert the mod into the database and index its identity
     pub async fn insert_into_db(self) -> Result<()> {
         tauri::async_runtime::spawn_blocking(move || {
             let tree = DB.open_tree(DB_MOD_TREE_NAME);
 
-            tree.b_insert(&Key::new(&self.uid.to_string()), &self)
+            tree.b_insert(&Key::new(&self.uid.to_string()), &self)?;
+            self.index_identities()
         })
         .await??;
 
         Ok(())
     }
 
-    /// Save the mod's archive file in the registry folder
-    pub async fn save_in_registry(&self, mod_info: ModArchive) -> Result<()> {
-        let uid = self.uid;
-        let extension = self.archive_file_extension.clone();
+    /// Insert the identities of the mod into the identity index
+    pub(super) fn index_identities(&self) -> Result<()> {
+        let id_tree = DB.open_tree(DB_MOD_ID_TREE_NAME);
+
+        for identity in self.identities() {
+            id_tree.b_insert(&identity.key(), &self.uid)?;
+        }
+
+        Ok(())
+    }
+
+    /// Point all identities of the provided duplicate mod to this mod, so future additions of the duplicate are treated as this mod
+    pub fn adopt_identities_of(&self, duplicate_uid: u64) -> Result<()> {
+        let id_tree = DB.open_tree(DB_MOD_ID_TREE_NAME);
+
+        for key in Self::identity_keys(duplicate_uid)? {
+            id_tree.b_insert(&key, &self.uid)?;
+        }
+
+        Ok(())
+    }
+
+    /// All keys of the identity index that point to the provided mod uid
+    fn identity_keys(uid: u64) -> Result<Vec<Key<u64>>> {
+        let id_tree = DB.open_tree(DB_MOD_ID_TREE_NAME);
+
+        let mut keys = vec![];
+
+        for entry in id_tree.iter() {
+            let (key, value) = entry?;
+
+            if bincode::deserialize::<u64>(&value)? != uid {
+                continue;
+            }
+
+            let key = String::from_utf8(key.to_vec())
+                .map_err(|e| ModManagerError::DeSerialization { msg: e.to_string() })?;
+
+            keys.push(Key::new(&key));
+        }
+
+        Ok(keys)
+    }
+
+    /// Save the mod's archive file in the registry folder under its fingerprint
+    ///
+    /// An intact archive with the same content which is already in the registry is reused, a damaged one is replaced. Returns whether the archive has been written.
+    pub async fn save_in_registry(&self, mod_info: ModArchive) -> Result<bool> {
+        let archive_path = self.archive_path();
+        let fingerprint = self.fingerprint.clone();
         tauri::async_runtime::spawn_blocking(move || {
-            fs::copy(
-                &mod_info.path,
-                APP_SAVE_PATH
-                    .join(MOD_REGISTRY_PATH)
-                    .join(format!("{}.{}", uid, extension)),
-            )?;
+            if archive_path.is_file() && archive::hash_file(&archive_path)? == fingerprint {
+                log::debug!("Archive {:?} is already in the registry", archive_path);
+                return Ok(false);
+            }
 
-            Ok(())
+            // Copy to a temporary file first, so the registry never contains a partially written archive
+            let temp_path = archive_path.with_extension("part");
+            fs::copy(&mod_info.path, &temp_path)?;
+            fs::rename(&temp_path, &archive_path)?;
+
+            Ok(true)
         })
         .await?
     }
 
-    /// Delete the mod from the registry folder and DB
+    /// Delete the registry archive of the current version, if the version could not be added after saving it in the registry. The archive is kept if another mod uses it.
+    pub fn delete_registry_archive(&self) -> Result<()> {
+        let version = self.current_version();
+
+        if Self::is_archive_shared(self.uid, &version)? {
+            return Ok(());
+        }
+
+        version.delete_archive()
+    }
+
+    /// Delete the mod including all of its versions from the registry folder and DB
     ///
     /// # Caution
-    /// This does not check if the mod is still active. Make sure to check if the mod is active prior to deletion to avoid any mod files cluttering the game folder.
+    /// This does not check if the mod is still active in any installation. Make sure to check if the mod is active prior to deletion to avoid any mod files cluttering the game folder.
     pub async fn delete(self) -> Result<()> {
         tauri::async_runtime::spawn_blocking(move || {
             let tree = DB.open_tree(DB_MOD_TREE_NAME);
+            let id_tree = DB.open_tree(DB_MOD_ID_TREE_NAME);
 
             tree.b_remove(&Key::<Mod>::new(&self.uid.to_string()))?;
 
-            // Remove the mod from the registry
-            match fs::remove_file(
-                APP_SAVE_PATH
-                    .join(MOD_REGISTRY_PATH)
-                    .join(format!("{}.{}", self.uid, self.archive_file_extension)),
-            ) {
-                Ok(_) => Ok(()),
-                Err(e) => match e.kind() {
-                    // Ignore not found error as we are deleting the mod anyways
-                    std::io::ErrorKind::NotFound => Ok(()),
-                    _ => Err(e),
-                },
+            for key in Self::identity_keys(self.uid)? {
+                id_tree.b_remove(&key)?;
             }
-            .map_err(|e| e.into())
+
+            // Remove all versions of the mod from the registry. Versions without fingerprint have no archive.
+            for version in self
+                .versions()
+                .iter()
+                .filter(|version| !version.fingerprint.is_empty())
+            {
+                if Self::is_archive_shared(self.uid, version)? {
+                    log::debug!(
+                        "Keeping archive {:?} which is used by another mod",
+                        version.archive_path()
+                    );
+                    continue;
+                }
+
+                version.delete_archive()?;
+            }
+
+            for version in self.versions() {
+                if Self::is_thumbnail_shared(self.uid, &version)? {
+                    continue;
+                }
+
+                version.delete_thumbnail()?;
+            }
+
+            Ok(())
         })
         .await?
     }
 
-    pub fn set_active(&mut self) -> Result<()> {
+    /// Whether another registered mod has a version stored in the same registry archive as the provided version
+    fn is_archive_shared(uid: u64, version: &ModVersion) -> Result<bool> {
+        let tree = DB.open_tree(DB_MOD_TREE_NAME);
+        let archive_path = version.archive_path();
+
+        for entry in tree.iter() {
+            let (_, value) = entry?;
+            let modification = bincode::deserialize::<Mod>(&value)?;
+
+            if modification.uid != uid
+                && modification
+                    .versions()
+                    .iter()
+                    .any(|other| other.archive_path() == archive_path)
+            {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Whether any version of another mod uses the thumbnail of the provided version, like the versions of a merged duplicate
+    fn is_thumbnail_shared(uid: u64, version: &ModVersion) -> Result<bool> {
+        let thumbnail = match &version.thumbnail {
+            Some(thumbnail) => thumbnail,
+            None => return Ok(false),
+        };
+
         let tree = DB.open_tree(DB_MOD_TREE_NAME);
 
-        self.is_active = true;
+        for entry in tree.iter() {
+            let (_, value) = entry?;
+            let modification = bincode::deserialize::<Mod>(&value)?;
+
+            if modification.uid != uid
+                && modification
+                    .versions()
+                    .iter()
+                    .any(|other| other.thumbnail.as_ref() == Some(thumbnail))
+            {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub fn set_active(&mut self, installation: &str) -> Result<()> {
+        let tree = DB.open_tree(DB_MOD_TREE_NAME);
+
+        self.mark_active(installation);
 
         tree.b_insert(&Key::<Mod>::new(&self.uid.to_string()), self)?;
 
         Ok(())
     }
 
-    pub fn deactivate(&mut self) -> Result<()> {
+    /// Marks the mod active in the installation without storing it in the DB
+    pub fn mark_active(&mut self, installation: &str) {
+        if !self.is_active_in(installation) {
+            self.active_installations.push(installation.to_owned());
+        }
+    }
+
+    /// Marks the mod inactive in the installation without storing it in the DB
+    pub fn mark_inactive(&mut self, installation: &str) {
+        self.active_installations
+            .retain(|active_installation| active_installation != installation);
+    }
+
+    pub fn deactivate(&mut self, installation: &str) -> Result<()> {
         let tree = DB.open_tree(DB_MOD_TREE_NAME);
 
-        self.is_active = false;
+        self.mark_inactive(installation);
 
         tree.b_insert(&Key::<Mod>::new(&self.uid.to_string()), self)?;
 
         Ok(())
     }
 }
+
+/// Layout of [`Mod`] as it was stored in the DB by modloader v0.1.0
+#[derive(Deserialize)]
+pub(super) struct LegacyMod {
+    name: String,
+    uid: u64,
+    archive_file_extension: String,
+    author: Option<String>,
+    version: Option<Version>,
+    info: Option<String>,
+    injection: InjectionType,
+    is_active: bool,
+}
+
+impl LegacyMod {
+    /// Path of the archive file of the legacy mod in the registry, which was named after the uid of the mod
+    pub(super) fn archive_path(&self) -> PathBuf {
+        archive_path(&self.uid.to_string(), &self.archive_file_extension)
+    }
+
+    /// Format of the legacy archive based on its extension, used if the archive can not be inspected
+    pub(super) fn archive_format_from_extension(&self) -> ArchiveFormat {
+        ArchiveFormat::from_extension(&self.archive_file_extension).unwrap_or(ArchiveFormat::Zip)
+    }
+
+    /// Converts the legacy mod into the current [`Mod`] layout. Active legacy mods are active in the provided installation.
+    pub(super) fn migrate(
+        self,
+        installation: &str,
+        fingerprint: &str,
+        archive_format: ArchiveFormat,
+    ) -> Mod {
+        Mod {
+            name: self.name,
+            uid: self.uid,
+            id: None,
+            fingerprint: fingerprint.to_owned(),
+            archive_format,
+            path_prefix: None,
+            development_path: None,
+            author: self.author,
+            version: self.version,
+            info: self.info,
+            injection: self.injection,
+            active_installations: if self.is_active {
+                vec![installation.to_owned()]
+            } else {
+                vec![]
+            },
+            declared_languages: None,
+            target_languages: None,
+            previous_versions: vec![],
+            metadata: ModMetadata::default(),
+            translations: ModTranslations::default(),
+            preview: None,
+            thumbnail: None,
+            option_groups: vec![],
+            selected_options: BTreeMap::new(),
+            file_rules: vec![],
+        }
+    }
+}
+
+/// The kind of change when replacing an installed mod version with another one
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum VersionChange {
+    Upgrade,
+    /// The same version is installed again, e.g. to repair a corrupted registry archive
+    Reinstall,
+    Downgrade,
+    /// Switch between a stable release and a pre-release
+    PrereleaseSwitch,
+    /// At least one of the mods has no version information
+    Unknown,
+}
+
+/// The text of a modinfo.json, which is translated if translations exist
+fn translated(text: String, translations: &BTreeMap<String, String>) -> LocalizedText {
+    if translations.is_empty() {
+        LocalizedText::Text(text)
+    } else {
+        LocalizedText::Translations(translations.clone())
+    }
+}
+
+/// Converts the language codes of a modinfo.json into game languages
+fn game_languages(codes: &[String]) -> Result<Vec<String>> {
+    codes
+        .iter()
+        .map(|code| {
+            config::game_language_from_code(code).map_err(|_| ModManagerError::InvalidModInfo {
+                msg: format!("Unsupported game language '{}'", code),
+            })
+        })
+        .collect()
+}
+
+/// Converts game languages into the language codes of a modinfo.json
+fn language_codes(languages: &[String]) -> Vec<String> {
+    languages
+        .iter()
+        .filter_map(|language| config::game_language_to_code(language).ok())
+        .collect()
+}
+
+/// Path of the archive file with the provided name in the registry
+fn archive_path(file_name: &str, extension: &str) -> PathBuf {
+    DATA_PATH
+        .join(MOD_REGISTRY_PATH)
+        .join(format!("{}.{}", file_name, extension))
+}
+
+/// The identity of a mod. Mods with the same identity are treated as versions of the same mod.
+pub enum ModIdentity {
+    /// Stable id declared by the mod author
+    Id(String),
+    /// Hash of the mod archive for mods without an id
+    Fingerprint(String),
+    /// Folder of a development mod
+    Directory(PathBuf),
+}
+
+impl ModIdentity {
+    /// The key of the identity in the [`DB_MOD_ID_TREE_NAME`] index
+    fn key(&self) -> Key<u64> {
+        match self {
+            ModIdentity::Id(id) => Key::new(&format!("id:{}", id)),
+            ModIdentity::Fingerprint(fingerprint) => {
+                Key::new(&format!("fingerprint:{}", fingerprint))
+            }
+            ModIdentity::Directory(path) => Key::new(&format!("directory:{}", path.display())),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! The sources the files of a mod are read from
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::archive::{ArchiveFormat, ModArchive, ModInfo};
+use super::directory::ModDirectory;
+use super::error::Result;
+
+/// A mod archive or an unpacked mod folder
+#[derive(Clone)]
+pub enum ModSource {
+    Archive(ModArchive),
+    /// Folder of a development mod, whose files are read directly from the folder
+    Directory(ModDirectory),
+}
+
+impl ModSource {
+    pub fn name(&self) -> &str {
+        match self {
+            ModSource::Archive(archive) => &archive.name,
+            ModSource::Directory(directory) => &directory.name,
+        }
+    }
+
+    /// Format of the archive. Folders are handled as zip archives, as they are packed into one when exported.
+    pub fn format(&self) -> ArchiveFormat {
+        match self {
+            ModSource::Archive(archive) => archive.format,
+            ModSource::Directory(_) => ArchiveFormat::Zip,
+        }
+    }
+
+    /// SHA-256 hash of the archive. Folders are not stored in the registry and have no fingerprint.
+    pub async fn get_fingerprint(&self) -> Result<String> {
+        match self {
+            ModSource::Archive(archive) => archive.get_fingerprint().await,
+            ModSource::Directory(_) => Ok(String::new()),
+        }
+    }
+
+    pub async fn get_modinfo(&self) -> Result<Option<ModInfo>> {
+        match self {
+            ModSource::Archive(archive) => archive.get_modinfo().await,
+            ModSource::Directory(directory) => directory.get_modinfo().await,
+        }
+    }
+
+    pub async fn get_changelog_file(&self) -> Result<Option<String>> {
+        match self {
+            ModSource::Archive(archive) => archive.get_changelog_file().await,
+            ModSource::Directory(directory) => directory.get_changelog_file().await,
+        }
+    }
+
+    pub async fn detect_layout_prefix(
+        &self,
+        has_file_rules: bool,
+        localization_path: Option<&Path>,
+    ) -> Result<Option<String>> {
+        match self {
+            ModSource::Archive(archive) => {
+                archive
+                    .detect_layout_prefix(has_file_rules, localization_path)
+                    .await
+            }
+            ModSource::Directory(directory) => {
+                directory
+                    .detect_layout_prefix(has_file_rules, localization_path)
+                    .await
+            }
+        }
+    }
+
+    pub async fn get_file_hashes(&self, path_prefix: &str) -> Result<HashMap<String, String>> {
+        match self {
+            ModSource::Archive(archive) => archive.get_file_hashes(path_prefix).await,
+            ModSource::Directory(directory) => directory.get_file_hashes(path_prefix).await,
+        }
+    }
+
+    /// Gets all paths of the files and dirs which are injected into the game
+    pub async fn get_dirs_and_files(
+        &self,
+        path_prefix: &str,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        match self {
+            ModSource::Archive(archive) => archive.get_archive_dirs_and_files(path_prefix).await,
+            ModSource::Directory(directory) => directory.get_dirs_and_files(path_prefix).await,
+        }
+    }
+
+    /// Opens the source for reading single files. This is blocking and has to be called inside of a blocking task.
+    pub fn reader(&self) -> Result<SourceReader> {
+        match self {
+            ModSource::Archive(archive) => Ok(SourceReader::Archive(File::open(&archive.path)?)),
+            ModSource::Directory(directory) => Ok(SourceReader::Directory(directory.path.clone())),
+        }
+    }
+}
+
+/// Blocking access to the files of a [`ModSource`]
+pub enum SourceReader {
+    Archive(File),
+    Directory(PathBuf),
+}
+
+impl SourceReader {
+    /// Writes the content of the file with the provided path to the target file
+    pub fn extract(&self, path: &str, mut target: &File) -> Result<()> {
+        match self {
+            SourceReader::Archive(archive) => {
+                compress_tools::uncompress_archive_file(archive, target, path)?;
+            }
+            SourceReader::Directory(directory) => {
+                io::copy(&mut File::open(directory.join(path))?, &mut target)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the content of the file with the provided path
+    pub fn read(&self, path: &str) -> Result<Vec<u8>> {
+        match self {
+            SourceReader::Archive(archive) => {
+                let mut file = vec![];
+                compress_tools::uncompress_archive_file(archive, &mut file, path)?;
+
+                Ok(file)
+            }
+            SourceReader::Directory(directory) => Ok(fs::read(directory.join(path))?),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Automatic import of mod archives which are placed in the watch folder
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Manager};
+
+use crate::config::WatchFolder;
+
+use super::error::Result;
+use super::import::OverwriteResolver;
+use super::ModManager;
+
+/// Event which is sent to the frontend with the [`super::ImportReport`] of every archive imported from the watch folder
+pub const WATCH_FOLDER_IMPORT_EVENT: &str = "watch-folder-import";
+/// Extensions of files which are still being downloaded. They are renamed to their final name once complete.
+const TEMPORARY_EXTENSIONS: [&str; 5] = ["part", "crdownload", "download", "partial", "tmp"];
+/// Interval in which the size of a new file is checked until it does not change anymore
+const WRITE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Watches the watch folder and imports new archives once they are fully written. Watching stops once dropped.
+pub struct FolderWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl FolderWatcher {
+    pub fn start(app_handle: AppHandle, watch_folder: &WatchFolder) -> Result<Self> {
+        // A single resolver for the whole session, so an answer to OverwritePolicy::AskOnce applies to all files dropped into the folder
+        let overwrite_resolver = Arc::new(OverwriteResolver::new(watch_folder.overwrite_policy));
+        // Files which are currently waited for, as every write to a file causes an event
+        let pending_paths: Arc<Mutex<HashSet<PathBuf>>> = Arc::new(Mutex::new(HashSet::new()));
+        // Files which have already been imported, as reading or touching a file causes events as well
+        let imported_files: Arc<Mutex<HashMap<PathBuf, FileStamp>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(err) => {
+                        log::error!("Failed to watch the watch folder: {}", err);
+                        return;
+                    }
+                };
+
+                if let EventKind::Remove(_) = event.kind {
+                    let mut imported_files = imported_files
+                        .lock()
+                        .expect("Watch folder mutex has been poisoned");
+
+                    for path in &event.paths {
+                        imported_files.remove(path);
+                    }
+
+                    return;
+                }
+
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    return;
+                }
+
+                for path in event.paths {
+                    if is_temporary(&path) || is_imported(&imported_files, &path) {
+                        continue;
+                    }
+
+                    if !pending_paths
+                        .lock()
+                        .expect("Watch folder mutex has been poisoned")
+                        .insert(path.clone())
+                    {
+                        continue;
+                    }
+
+                    let app_handle = app_handle.clone();
+                    let pending_paths = pending_paths.clone();
+                    let imported_files = imported_files.clone();
+                    let overwrite_resolver = overwrite_resolver.clone();
+
+                    tauri::async_runtime::spawn(async move {
+                        if let Some(stamp) =
+                            import_when_written(&app_handle, &path, &overwrite_resolver).await
+                        {
+                            imported_files
+                                .lock()
+                                .expect("Watch folder mutex has been poisoned")
+                                .insert(path.clone(), stamp);
+                        }
+
+                        pending_paths
+                            .lock()
+                            .expect("Watch folder mutex has been poisoned")
+                            .remove(&path);
+                    });
+                }
+            })?;
+
+        watcher.watch(Path::new(&watch_folder.path), RecursiveMode::NonRecursive)?;
+
+        log::info!("Watching folder {} for new mods", watch_folder.path);
+
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+/// Size and modification time of a file, which change once the file is written again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    /// None if the path is no file or has been removed
+    fn read(path: &Path) -> Option<Self> {
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => Some(Self {
+                len: metadata.len(),
+                modified: metadata.modified().ok(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// If the file has already been imported and has not been written since
+fn is_imported(imported_files: &Mutex<HashMap<PathBuf, FileStamp>>, path: &Path) -> bool {
+    let imported_stamp = imported_files
+        .lock()
+        .expect("Watch folder mutex has been poisoned")
+        .get(path)
+        .copied();
+
+    imported_stamp.is_some() && imported_stamp == FileStamp::read(path)
+}
+
+/// Imports the file once it is fully written and sends the import report to the frontend. Files which are no archives are reported as failed imports.
+///
+/// Returns the stamp of the file once its import has been attempted, so it is not imported again until it changes.
+async fn import_when_written(
+    app_handle: &AppHandle,
+    path: &Path,
+    overwrite_resolver: &OverwriteResolver,
+) -> Option<FileStamp> {
+    let written_path = path.to_owned();
+    let stamp =
+        tauri::async_runtime::spawn_blocking(move || wait_until_written(&written_path)).await;
+
+    let stamp = match stamp {
+        Ok(Some(stamp)) => stamp,
+        _ => {
+            log::debug!("File {:?} has been removed from watch folder", path);
+            return None;
+        }
+    };
+
+    let window = app_handle.get_window("main")?;
+
+    log::info!("Importing mod archive {:?} from watch folder", path);
+
+    let report = app_handle
+        .state::<ModManager>()
+        .import_mods_with(
+            vec![path.to_string_lossy().to_string()],
+            overwrite_resolver,
+            window,
+        )
+        .await;
+
+    let report = match report.map(serde_json::to_value) {
+        Ok(Ok(report)) => report,
+        Ok(Err(err)) => {
+            log::error!("Failed to serialize import report: {}", err);
+            return Some(stamp);
+        }
+        Err(err) => {
+            log::error!("Failed to import {:?} from watch folder: {:?}", path, err);
+            return Some(stamp);
+        }
+    };
+
+    if let Err(err) = app_handle.emit_all(WATCH_FOLDER_IMPORT_EVENT, report) {
+        log::error!(
+            "Failed to send watch folder import event to frontend: {}",
+            err
+        );
+    }
+
+    Some(stamp)
+}
+
+/// Blocks until the size and modification time of the file stop changing. Returns None if the file has been removed in the meantime.
+fn wait_until_written(path: &Path) -> Option<FileStamp> {
+    let mut previous_stamp = None;
+
+    loop {
+        let stamp = FileStamp::read(path)?;
+
+        if stamp.len > 0 && previous_stamp == Some(stamp) {
+            return Some(stamp);
+        }
+
+        previous_stamp = Some(stamp);
+        thread::sleep(WRITE_CHECK_INTERVAL);
+    }
+}
+
+fn is_temporary(path: &Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .map(|extension| {
+            TEMPORARY_EXTENSIONS
+                .iter()
+                .any(|temporary| temporary.eq_ignore_ascii_case(extension))
+        })
+        .unwrap_or(false)
+}
@@ -21,7 +21,12 @@ export type ConfigError =
 	| { type: "DeSerialization"; msg: string }
 	| { type: "Io"; msg: string }
 	| { type: "GameLanguageNotSupported" }
-	| ({ type: "InvalidGamePath" } & InvalidGamePath);
+	| ({ type: "InvalidGamePath" } & InvalidGamePath)
+	| ({ type: "InvalidDataPath" } & InvalidDataPath)
+	| { type: "InstallationNotExisting" }
+	| { type: "InstallationAlreadyExisting" }
+	| { type: "InvalidInstallationName" }
+	| { type: "InvalidWatchFolder" };
 
 export type InvalidGamePath =
 	| { invalidGamePath: "NotExisting" }
@@ -30,27 +35,41 @@ export type InvalidGamePath =
 	| { invalidGamePath: "InvalidFolderName" }
 	| { invalidGamePath: "LocalizationNotFound" };
 
+export type InvalidDataPath =
+	| { invalidDataPath: "NotADirectory" }
+	| { invalidDataPath: "NotWritable" }
+	| { invalidDataPath: "InsideCurrentDataPath" }
+	| { invalidDataPath: "AlreadyContainsData" };
+
 export type ModManagerError =
 	| { type: "Io"; msg: string }
 	| { type: "Db"; msg: string }
 	| { type: "DeSerialization"; msg: string }
 	| ({ type: "InvalidArchive" } & InvalidArchive)
 	| { type: "ArchiveHandling"; msg: string }
+	| { type: "ArchiveCorrupted"; name: string }
 	| { type: "InvalidModInfo"; msg: string }
+	| { type: "InvalidPackManifest"; msg: string }
+	| { type: "ModloaderOutdated"; required: string }
 	| { type: "ModNotExisting" }
+	| { type: "ModVersionNotExisting" }
+	| { type: "ModOptionsInUse"; installations: Array<string> }
 	| { type: "ModAlreadyActive" }
 	| { type: "ModAlreadyDeactivated" }
-	| { type: "ModVersionMismatch"; mismatch: [string, string] }
+	| { type: "NotADevelopmentMod" }
 	| { type: "AppNotInitialized" }
 	| { type: "ModConflict"; conflict: Array<[string, string]> }
 	| ({ type: "ConfigError" } & ConfigError)
+	| { type: "LanguageNotInstalled"; language: string }
 	| { type: "TauriError"; msg: string };
 
 export type InvalidArchive =
 	| { invalidArchive: "PathNotExisting" }
 	| { invalidArchive: "PathNotFile" }
-	| { invalidArchive: "NoExtension" }
-	| { invalidArchive: "InvalidExtension" };
+	| { invalidArchive: "PathNotDirectory" }
+	| { invalidArchive: "EmptyFile" }
+	| { invalidArchive: "UnsupportedFormat" }
+	| { invalidArchive: "MultipleLocalizations" };
 
 /**
  * Invoke a command in the backend. This automatically handles unrecoverable app errors and forwards recoverable app errors. See the error.rs file in the backend code for more info.
@@ -67,7 +86,6 @@ export async function invokeBackend<T>(
 		return result;
 	} catch (error) {
 		let err = error as AppError;
-		console.log(error);
 
 		if (err.Unrecoverable) {
 			await message(
@@ -100,6 +118,8 @@ export function isError(data: any | RecoverableAppError): data is RecoverableApp
 	);
 }
 
+const UNHANDLED_MOD_MANAGER_ERROR = "Fatal unhandled ModManagerError";
+
 /**
  * Returns the specific error message translated to the currently active locale
  * @param error
@@ -112,6 +132,13 @@ export function getErrorMessage(error: RecoverableAppError): string {
 				return get(_)("error.GameLanguageNotSupported");
 			case "InvalidGamePath":
 				return get(_)(`error.invalidGamePath.${error.ConfigError.invalidGamePath}`);
+			case "InvalidDataPath":
+				return get(_)(`error.invalidDataPath.${error.ConfigError.invalidDataPath}`);
+			case "InstallationNotExisting":
+			case "InstallationAlreadyExisting":
+			case "InvalidInstallationName":
+			case "InvalidWatchFolder":
+				return get(_)(`error.${error.ConfigError.type}`);
 			default:
 				return "Fatal unhandled ConfigError";
 		}
@@ -123,8 +150,24 @@ export function getErrorMessage(error: RecoverableAppError): string {
 				});
 			case "InvalidArchive":
 				return get(_)(`error.invalidArchive.${error.ModManagerError.invalidArchive}`);
+			case "ArchiveCorrupted":
+				return get(_)("error.ArchiveCorrupted", { values: { modName: error.ModManagerError.name } });
 			case "InvalidModInfo":
 				return get(_)("error.InvalidModInfo", { values: { error: error.ModManagerError.msg } });
+			case "InvalidPackManifest":
+				return get(_)("error.InvalidPackManifest", {
+					values: { error: error.ModManagerError.msg }
+				});
+			case "ModVersionNotExisting":
+				return get(_)("error.ModVersionNotExisting");
+			case "ModOptionsInUse":
+				return get(_)("error.ModOptionsInUse", {
+					values: { installations: error.ModManagerError.installations.join(", ") }
+				});
+			case "ModloaderOutdated":
+				return get(_)("error.ModloaderOutdated", {
+					values: { required: error.ModManagerError.required }
+				});
 			case "ModConflict":
 				let conflictString = "";
 
@@ -133,17 +176,29 @@ export function getErrorMessage(error: RecoverableAppError): string {
 				});
 
 				return get(_)("error.ModConflict", { values: { conflicts: conflictString } });
-			case "ModVersionMismatch":
-				return get(_)("error.ModVersionMismatch", {
-					values: {
-						installedVersion: error.ModManagerError.mismatch[1],
-						newVersion: error.ModManagerError.mismatch[0]
-					}
+			case "LanguageNotInstalled":
+				return get(_)("error.LanguageNotInstalled", {
+					values: { language: error.ModManagerError.language }
 				});
 			default:
-				return "Fatal unhandled ModManagerError";
+				return UNHANDLED_MOD_MANAGER_ERROR;
 		}
 	}
 
 	return "Unknown";
 }
+
+/**
+ * Returns the message of a ModManagerError which is reported as part of a command result instead of being returned as error, e.g. in import reports
+ * @param error
+ * @returns the translated error message or the raw error details if the error is not recoverable
+ */
+export function getModManagerErrorMessage(error: ModManagerError): string {
+	const message = getErrorMessage({ ModManagerError: error });
+
+	if (message !== UNHANDLED_MOD_MANAGER_ERROR) {
+		return message;
+	}
+
+	return "msg" in error ? error.msg : error.type;
+}
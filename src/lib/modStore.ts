@@ -1,9 +1,10 @@
-import { writable, type Writable } from "svelte/store";
+import { get, writable, type Writable } from "svelte/store";
 import { appWindow } from "@tauri-apps/api/window";
 import { invoke } from "@tauri-apps/api";
 import type { UnlistenFn } from "@tauri-apps/api/event";
 
 let unlisten: UnlistenFn | null = null;
+let unlistenConfig: UnlistenFn | null = null;
 
 type ModTreeDataChangedPayload = {
 	InsertUpdate?: [number, Mod];
@@ -11,40 +12,178 @@ type ModTreeDataChangedPayload = {
 };
 
 type Mod = {
+	/** Stable id declared in the modinfo.json */
+	id: null | string;
 	author: null | string;
 	info: null | string;
 	injection: string;
+	active_installations: Array<string>;
+	/** Whether the mod is active in the selected installation, derived from active_installations */
 	is_active: boolean;
+	/** Name in the app language if the mod is translated */
 	name: string;
 	uid: number;
 	version: null | string;
+	/** SHA-256 hash of the archive of the current version */
+	fingerprint: string;
+	/** Folder a development mod is read from, null for mods stored in the registry */
+	development_path: null | string;
+	previous_versions: Array<ModVersion>;
+	metadata: ModMetadata;
+	/** File name of the thumbnail of the preview image, null if the mod has no preview image */
+	thumbnail: null | string;
+	option_groups: Array<OptionGroup>;
+	/** Selected choice names keyed by group name, groups without selection use their first choice */
+	selected_options: Record<string, string>;
+	/** Game languages declared by the modinfo.json, null if the mod is installed into the game language of the installation */
+	declared_languages: null | Array<string>;
+	/** Game languages selected by the user, which take precedence over the declared languages */
+	target_languages: null | Array<string>;
 };
 
+/** A group of alternatives of a mod of which exactly one choice is injected */
+export type OptionGroup = {
+	name: string;
+	choices: Array<{ name: string; folder: string }>;
+};
+
+/** Optional metadata declared in the extended fields of the modinfo.json */
+export type ModMetadata = {
+	/** Short summary of the mod, while info contains the full description */
+	description: null | string;
+	homepage: null | string;
+	license: null | string;
+	tags: Array<string>;
+	min_modloader_version: null | string;
+	/** Game languages the mod has been made for */
+	supported_languages: null | Array<string>;
+};
+
+/** A version of a mod kept in the registry */
+export type ModVersion = {
+	fingerprint: string;
+	name: string;
+	id: null | string;
+	author: null | string;
+	version: null | string;
+	info: null | string;
+	injection: string;
+	metadata: ModMetadata;
+	thumbnail: null | string;
+	option_groups: Array<OptionGroup>;
+};
+
+export type GameInstallation = {
+	name: string;
+	path: string;
+	language: string;
+};
+
+/** Payload of the config-changed event */
+export type ModloaderConfig = {
+	version: number;
+	app_language: string | null;
+	installations: Array<GameInstallation>;
+	selected_installation: string | null;
+	dark_theme: boolean;
+};
+
+/** Name of the game installation all mod operations are applied to */
+export const selectedInstallation: Writable<string | null> = writable(null);
+
+function withActiveState(mod: Mod): Mod {
+	const installation = get(selectedInstallation);
+	mod.is_active = installation !== null && mod.active_installations.includes(installation);
+	return mod;
+}
+
+/**
+ * Fetch the configured installations from the backend and update the selected installation
+ */
+export async function refreshInstallations(): Promise<Array<GameInstallation>> {
+	const [installations, selected]: [Array<GameInstallation>, string | null] = await invoke(
+		"get_installations"
+	);
+
+	selectedInstallation.set(selected);
+
+	return installations;
+}
+
+/**
+ * Fetch all mods from the backend, with their name and info translated into the app language
+ */
+async function fetchMods(): Promise<Map<number, Mod>> {
+	const data = await invoke("get_initial_mod_data");
+	const entries: Array<Mod> = Object.values(data as Object);
+	let keys = Object.keys(data as Object).map((stringKey) => parseInt(stringKey));
+
+	return new Map(keys.map((key, idx) => [key, withActiveState(entries[idx])]));
+}
+
+/** App language the mods have been translated into */
+let modLanguage: string | null = null;
+
 export const mods: Writable<Map<number, Mod>> = writable(new Map(), (set) => {
-	invoke("get_initial_mod_data").then((data) => {
-		const entries: Array<Mod> = Object.values(data as Object);
-		let keys = Object.keys(data as Object).map((stringKey) => parseInt(stringKey));
-		set(new Map(keys.map((key, idx) => [key, entries[idx]])));
-
-		appWindow
-			.listen("mod-tree-data-changed", (event) => {
-				const payload = event.payload as ModTreeDataChangedPayload;
-
-				if (payload.InsertUpdate) {
-					mods.update((map) => map.set(payload.InsertUpdate![0], payload.InsertUpdate![1]));
-				} else if (payload.Delete) {
-					mods.update((map) => {
-						map.delete(payload.Delete!);
-						return map;
-					});
-				}
-			})
-			.then((unlistener) => {
-				unlisten = unlistener;
-			});
+	const unsubscribeInstallation = selectedInstallation.subscribe(() => {
+		mods.update((map) => {
+			map.forEach((mod) => withActiveState(mod));
+			return map;
+		});
 	});
 
+	appWindow
+		.listen("config-changed", (event) => {
+			const config = event.payload as ModloaderConfig;
+
+			selectedInstallation.set(config.selected_installation);
+
+			// Names and infos are translated by the backend, so they have to be fetched again
+			if (config.app_language !== modLanguage) {
+				modLanguage = config.app_language;
+				fetchMods().then((map) => set(map));
+			}
+		})
+		.then((unlistener) => {
+			unlistenConfig = unlistener;
+		});
+
+	refreshInstallations()
+		.then(() => invoke<string | null>("get_app_language"))
+		.then((language) => {
+			modLanguage = language;
+			return fetchMods();
+		})
+		.then((map) => {
+			set(map);
+
+			appWindow
+				.listen("mod-tree-data-changed", (event) => {
+					const payload = event.payload as ModTreeDataChangedPayload;
+
+					if (payload.InsertUpdate) {
+						mods.update((map) =>
+							map.set(payload.InsertUpdate![0], withActiveState(payload.InsertUpdate![1]))
+						);
+					} else if (payload.Delete) {
+						mods.update((map) => {
+							map.delete(payload.Delete!);
+							return map;
+						});
+					}
+				})
+				.then((unlistener) => {
+					unlisten = unlistener;
+				});
+		});
+
 	return () => {
+		unsubscribeInstallation();
+
+		if (unlistenConfig) {
+			unlistenConfig();
+		}
+
 		if (unlisten) {
 			unlisten();
 		}